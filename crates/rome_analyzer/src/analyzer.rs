@@ -0,0 +1,63 @@
+use crate::{Rule, RuleContext};
+use rslint_errors::{file::FileId, Diagnostic, Severity};
+use rslint_parser::{SyntaxKind, SyntaxNode};
+use std::collections::HashMap;
+
+/// Runs the enabled rules over the syntax trees, it's created by
+/// [RuleRegistry::analyzer](crate::RuleRegistry::analyzer)
+pub struct Analyzer<'a> {
+	/// The enabled rules, with the severity of their diagnostics
+	rules: Vec<(&'a dyn Rule, Severity)>,
+	/// The values are indexes in `rules`
+	rules_by_kind: HashMap<SyntaxKind, Vec<usize>>,
+}
+
+impl<'a> Analyzer<'a> {
+	pub(crate) fn new(rules: Vec<(&'a dyn Rule, Severity)>) -> Self {
+		let mut rules_by_kind: HashMap<SyntaxKind, Vec<usize>> = HashMap::new();
+		for (index, (rule, _)) in rules.iter().enumerate() {
+			for &kind in rule.kinds() {
+				rules_by_kind.entry(kind).or_default().push(index);
+			}
+		}
+		Self {
+			rules,
+			rules_by_kind,
+		}
+	}
+
+	/// The names of the enabled rules
+	pub fn rule_names(&self) -> impl Iterator<Item = &'static str> + '_ {
+		self.rules.iter().map(|(rule, _)| rule.name())
+	}
+
+	/// The names of the enabled rules, with the severity of their diagnostics
+	pub fn rules(&self) -> impl Iterator<Item = (&'static str, Severity)> + '_ {
+		self.rules
+			.iter()
+			.map(|(rule, severity)| (rule.name(), *severity))
+	}
+
+	/// Visits the tree of the file `file_id`, returning the diagnostics of the rules in the
+	/// order of the nodes they belong to
+	pub fn analyze(&self, root: &SyntaxNode, file_id: FileId) -> Vec<Diagnostic> {
+		let mut diagnostics = Vec::new();
+		for node in root.descendants() {
+			let indexes = match self.rules_by_kind.get(&node.kind()) {
+				Some(indexes) => indexes,
+				None => continue,
+			};
+			for &index in indexes {
+				let (rule, severity) = self.rules[index];
+				let mut ctx = RuleContext {
+					file_id,
+					name: rule.name(),
+					severity,
+					diagnostics: &mut diagnostics,
+				};
+				rule.check(&node, &mut ctx);
+			}
+		}
+		diagnostics
+	}
+}
@@ -0,0 +1,211 @@
+//! Applies the fixes suggested by the rules.
+//!
+//! The fixes of a round are merged into one [TextEdit], a fix that overlaps with a fix already
+//! merged is left for the next round, where the rules are run again over the fixed code. The
+//! rounds go on until there's nothing left to fix.
+//!
+//! The fixed code is parsed after each round, a round that introduces syntax errors is discarded:
+//! its fixes are tried one by one, keeping the first one that doesn't break the code.
+use rslint_errors::{Applicability, CodeSuggestion, Diagnostic, Span, SuggestionChange};
+use rslint_text_edit::{Indel, TextEdit};
+
+/// The maximum number of rounds, in case the fixes keep undoing each other
+const MAX_ROUNDS: usize = 10;
+
+/// Which fixes are applied
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum FixMode {
+	/// Only the fixes that are always correct, see [Applicability::Always]
+	Safe,
+	/// The fixes that may change the behaviour of the code as well, see
+	/// [Applicability::MaybeIncorrect]
+	Unsafe,
+}
+
+impl FixMode {
+	fn accepts(self, applicability: Applicability) -> bool {
+		match applicability {
+			Applicability::Always => true,
+			Applicability::MaybeIncorrect => self == FixMode::Unsafe,
+			// the other fixes can't be applied without the user
+			Applicability::HasPlaceholders | Applicability::Unspecified => false,
+		}
+	}
+}
+
+/// The outcome of [apply_fixes]
+#[derive(Debug)]
+pub struct Fixed {
+	/// The code with the fixes applied
+	pub code: String,
+	/// The number of fixes that have been applied
+	pub applied: usize,
+	/// The diagnostics of the fixed code, the problems that couldn't be fixed
+	pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Applies the fixes of `diagnostics`, which have been reported for `source`.
+///
+/// `analyze` parses a fixed code and returns the diagnostics of the rules, or [None] when the code
+/// has syntax errors
+pub fn apply_fixes(
+	source: &str,
+	diagnostics: Vec<Diagnostic>,
+	mode: FixMode,
+	mut analyze: impl FnMut(&str) -> Option<Vec<Diagnostic>>,
+) -> Fixed {
+	let mut fixed = Fixed {
+		code: source.to_string(),
+		applied: 0,
+		diagnostics,
+	};
+
+	for _ in 0..MAX_ROUNDS {
+		let fixes = collect_fixes(&fixed.diagnostics, mode);
+		if fixes.is_empty() {
+			break;
+		}
+
+		let (edit, count) = merge_fixes(&fixes);
+		let code = apply(&fixed.code, &edit);
+		let round = match analyze(&code) {
+			Some(diagnostics) => Some((code, diagnostics, count)),
+			None => fixes.iter().find_map(|fix| {
+				let code = apply(&fixed.code, fix);
+				analyze(&code).map(|diagnostics| (code, diagnostics, 1))
+			}),
+		};
+
+		match round {
+			Some((code, diagnostics, count)) => {
+				fixed.code = code;
+				fixed.diagnostics = diagnostics;
+				fixed.applied += count;
+			}
+			// every fix breaks the code
+			None => break,
+		}
+	}
+	fixed
+}
+
+/// The edits of the suggestions accepted by `mode`, in the order of the diagnostics
+fn collect_fixes(diagnostics: &[Diagnostic], mode: FixMode) -> Vec<TextEdit> {
+	diagnostics
+		.iter()
+		.flat_map(|diagnostic| {
+			diagnostic.suggestions.iter().filter(move |suggestion| {
+				suggestion.span.file == diagnostic.file_id && mode.accepts(suggestion.applicability)
+			})
+		})
+		.map(suggestion_edit)
+		.collect()
+}
+
+fn suggestion_edit(suggestion: &CodeSuggestion) -> TextEdit {
+	let range = suggestion.span.range.as_text_range();
+	match &suggestion.substitution {
+		SuggestionChange::String(replacement) => TextEdit::replace(range, replacement.clone()),
+		// the indels are relative to the start of the span
+		SuggestionChange::Indels(indels) => {
+			let mut builder = TextEdit::builder();
+			for indel in indels {
+				let Indel { insert, delete } = indel.clone();
+				builder.replace(delete + range.start(), insert);
+			}
+			builder.finish()
+		}
+	}
+}
+
+/// Merges the fixes that don't overlap with the fixes before them, returning the merged edit
+/// and the number of merged fixes
+fn merge_fixes(fixes: &[TextEdit]) -> (TextEdit, usize) {
+	let mut edit = TextEdit::default();
+	let mut count = 0;
+	for fix in fixes {
+		if edit.union(fix.clone()).is_ok() {
+			count += 1;
+		}
+	}
+	(edit, count)
+}
+
+fn apply(code: &str, edit: &TextEdit) -> String {
+	let mut code = code.to_string();
+	edit.apply(&mut code);
+	code
+}
+
+#[cfg(test)]
+mod test {
+	use super::{apply_fixes, FixMode};
+	use crate::{RuleRegistry, RulesConfiguration};
+	use rslint_errors::{Applicability, Diagnostic};
+	use rslint_parser::parse_text;
+
+	fn analyze(code: &str) -> Option<Vec<Diagnostic>> {
+		let parse = parse_text(code, 0);
+		if !parse.errors().is_empty() {
+			return None;
+		}
+		let registry = RuleRegistry::new();
+		let analyzer = registry.analyzer(&RulesConfiguration::default());
+		Some(analyzer.analyze(&parse.syntax(), 0))
+	}
+
+	fn fix(code: &str, mode: FixMode) -> (String, usize, usize) {
+		let fixed = apply_fixes(code, analyze(code).unwrap(), mode, analyze);
+		(fixed.code, fixed.applied, fixed.diagnostics.len())
+	}
+
+	#[test]
+	fn applies_safe_fixes() {
+		let code = "debugger;\nif (a == b) { debugger; }\n";
+		assert_eq!(
+			fix(code, FixMode::Safe),
+			("\nif (a == b) {  }\n".to_string(), 2, 1)
+		);
+		assert_eq!(
+			fix(code, FixMode::Unsafe),
+			("\nif (a === b) {  }\n".to_string(), 3, 0)
+		);
+	}
+
+	#[test]
+	fn defers_overlapping_fixes() {
+		let code = "if (a == b) { debugger; }\n";
+		// it overlaps with the fix of noDoubleEquals, which comes after it
+		let rewrite = Diagnostic::error(0, "test", "Rewrite the comparison").suggestion(
+			4usize..10,
+			"rewrite it",
+			"a === b",
+			Applicability::Always,
+		);
+		let mut diagnostics = vec![rewrite];
+		diagnostics.extend(analyze(code).unwrap());
+
+		let fixed = apply_fixes(code, diagnostics, FixMode::Unsafe, analyze);
+		assert_eq!(fixed.code, "if (a === b) {  }\n");
+		assert_eq!(fixed.applied, 2);
+		assert!(fixed.diagnostics.is_empty());
+	}
+
+	#[test]
+	fn keeps_the_code_valid() {
+		let code = "if (a) b;\ndebugger;\n";
+		let broken = Diagnostic::error(0, "test", "Break the condition").suggestion(
+			0usize..6,
+			"break it",
+			"if (",
+			Applicability::Always,
+		);
+		let mut diagnostics = vec![broken];
+		diagnostics.extend(analyze(code).unwrap());
+
+		// the fixes are tried one by one, as the round breaks the code
+		let fixed = apply_fixes(code, diagnostics, FixMode::Safe, analyze);
+		assert_eq!(fixed.code, "if (a) b;\n\n");
+		assert_eq!(fixed.applied, 1);
+	}
+}
@@ -0,0 +1,92 @@
+//! The linter of Rome.
+//!
+//! A [Rule] visits the nodes of some kinds and reports the problems it finds as [Diagnostic]s,
+//! which can carry a [CodeSuggestion](rslint_errors::CodeSuggestion) that fixes the problem.
+//! The rules are collected in a [RuleRegistry], which creates an [Analyzer] that runs the rules
+//! enabled by a [RulesConfiguration] over the syntax tree of a file.
+//!
+//! The suggestions that can be applied without the user are applied by [apply_fixes].
+use rslint_errors::{file::FileId, Diagnostic, Severity};
+use rslint_parser::{SyntaxKind, SyntaxNode};
+use std::fmt;
+
+mod analyzer;
+mod fix;
+mod registry;
+pub mod rules;
+
+pub use analyzer::Analyzer;
+pub use fix::{apply_fixes, FixMode, Fixed};
+pub use registry::{RegistrationError, RuleLevel, RuleRegistry, RulesConfiguration};
+
+/// The kind of problems found by a rule
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum RuleCategory {
+	/// The code is wrong or useless
+	Correctness,
+	/// The code is probably not what the author intended
+	Suspicious,
+	/// The code could be written in a more consistent or idiomatic way
+	Style,
+}
+
+impl fmt::Display for RuleCategory {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			RuleCategory::Correctness => fmt.write_str("correctness"),
+			RuleCategory::Suspicious => fmt.write_str("suspicious"),
+			RuleCategory::Style => fmt.write_str("style"),
+		}
+	}
+}
+
+/// A check of the linter.
+///
+/// Rules are shared between the threads that lint the files, hence they must be [Send] and [Sync]
+pub trait Rule: Send + Sync {
+	/// The name of the rule in camel case, like `noDebugger`. It's the name used in the
+	/// configuration, and the code of the diagnostics is `lint/` followed by the name
+	fn name(&self) -> &'static str;
+
+	fn category(&self) -> RuleCategory;
+
+	/// The severity of the diagnostics, unless the configuration sets another level for the rule
+	fn default_severity(&self) -> Severity {
+		Severity::Error
+	}
+
+	/// The kinds of the nodes that the rule visits, [Rule::check] is only called with them
+	fn kinds(&self) -> &'static [SyntaxKind];
+
+	/// Checks a node, reporting the problems to `ctx`
+	fn check(&self, node: &SyntaxNode, ctx: &mut RuleContext);
+}
+
+/// Collects the diagnostics of a rule for the file being analyzed
+pub struct RuleContext<'a> {
+	file_id: FileId,
+	name: &'static str,
+	severity: Severity,
+	diagnostics: &'a mut Vec<Diagnostic>,
+}
+
+impl RuleContext<'_> {
+	/// The id of the file being analyzed
+	pub fn file_id(&self) -> FileId {
+		self.file_id
+	}
+
+	/// Creates a diagnostic of the rule, with the configured severity
+	pub fn diagnostic(&self, title: impl Into<String>) -> Diagnostic {
+		Diagnostic::new_with_code(
+			self.file_id,
+			self.severity,
+			title,
+			Some(format!("lint/{}", self.name)),
+		)
+	}
+
+	pub fn report(&mut self, diagnostic: Diagnostic) {
+		self.diagnostics.push(diagnostic);
+	}
+}
@@ -0,0 +1,188 @@
+use crate::rules::{NoDebugger, NoDoubleEquals, NoSparseArray};
+use crate::{Analyzer, Rule};
+use rslint_errors::Severity;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// The level of a rule set in the configuration
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum RuleLevel {
+	/// The rule is disabled
+	Off,
+	/// The rule reports warnings
+	Warn,
+	/// The rule reports errors
+	Error,
+}
+
+impl FromStr for RuleLevel {
+	type Err = &'static str;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"off" => Ok(Self::Off),
+			"warn" => Ok(Self::Warn),
+			"error" => Ok(Self::Error),
+			_ => Err("Value not supported for RuleLevel"),
+		}
+	}
+}
+
+/// Enables and disables the rules, and overrides their severity
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct RulesConfiguration {
+	/// The rules that are not set keep their default severity
+	levels: HashMap<String, RuleLevel>,
+}
+
+impl RulesConfiguration {
+	pub fn set(&mut self, name: impl Into<String>, level: RuleLevel) {
+		self.levels.insert(name.into(), level);
+	}
+
+	/// The level set for the rule, if any
+	pub fn level(&self, name: &str) -> Option<RuleLevel> {
+		self.levels.get(name).copied()
+	}
+
+	/// Overrides the levels of this configuration with the levels set in `other`
+	pub fn merge(&mut self, other: &RulesConfiguration) {
+		for (name, level) in &other.levels {
+			self.levels.insert(name.clone(), *level);
+		}
+	}
+}
+
+/// A rule can't be registered, as another rule has the same name
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RegistrationError {
+	pub name: &'static str,
+}
+
+impl fmt::Display for RegistrationError {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(fmt, "the rule \"{}\" is already registered", self.name)
+	}
+}
+
+impl Error for RegistrationError {}
+
+/// The rules known by the linter
+pub struct RuleRegistry {
+	rules: Vec<Box<dyn Rule>>,
+}
+
+impl Default for RuleRegistry {
+	fn default() -> Self {
+		let mut registry = Self::empty();
+		let rules: [Box<dyn Rule>; 3] = [
+			Box::new(NoDebugger),
+			Box::new(NoDoubleEquals),
+			Box::new(NoSparseArray),
+		];
+		for rule in rules {
+			registry
+				.register(rule)
+				.expect("the default rules have different names");
+		}
+		registry
+	}
+}
+
+impl RuleRegistry {
+	/// Creates a registry with the rules of Rome
+	pub fn new() -> Self {
+		Default::default()
+	}
+
+	pub fn empty() -> Self {
+		Self { rules: Vec::new() }
+	}
+
+	/// Adds a rule, unless another rule has the same name
+	pub fn register(&mut self, rule: Box<dyn Rule>) -> Result<(), RegistrationError> {
+		if self.get(rule.name()).is_some() {
+			return Err(RegistrationError { name: rule.name() });
+		}
+		self.rules.push(rule);
+		Ok(())
+	}
+
+	pub fn get(&self, name: &str) -> Option<&dyn Rule> {
+		self.rules
+			.iter()
+			.find(|rule| rule.name() == name)
+			.map(|rule| rule.as_ref())
+	}
+
+	pub fn rules(&self) -> impl Iterator<Item = &dyn Rule> + '_ {
+		self.rules.iter().map(|rule| rule.as_ref())
+	}
+
+	/// Creates the analyzer that runs the rules enabled by `configuration`. The rules are
+	/// enabled by default, with their default severity
+	pub fn analyzer(&self, configuration: &RulesConfiguration) -> Analyzer<'_> {
+		let rules = self
+			.rules()
+			.filter_map(|rule| {
+				let severity = match configuration.level(rule.name()) {
+					Some(RuleLevel::Off) => return None,
+					Some(RuleLevel::Warn) => Severity::Warning,
+					Some(RuleLevel::Error) => Severity::Error,
+					None => rule.default_severity(),
+				};
+				Some((rule, severity))
+			})
+			.collect();
+		Analyzer::new(rules)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::rules::NoDebugger;
+	use crate::{RegistrationError, RuleLevel, RuleRegistry, RulesConfiguration};
+	use rslint_errors::Severity;
+	use rslint_parser::parse_text;
+
+	#[test]
+	fn configures_rules() {
+		let registry = RuleRegistry::new();
+		let root = parse_text("debugger;\nif (a == b) {}\n", 0).syntax();
+
+		let analyzer = registry.analyzer(&RulesConfiguration::default());
+		let codes: Vec<_> = analyzer
+			.analyze(&root, 0)
+			.into_iter()
+			.map(|diagnostic| (diagnostic.code.unwrap(), diagnostic.severity))
+			.collect();
+		assert_eq!(
+			codes,
+			vec![
+				("lint/noDebugger".to_string(), Severity::Error),
+				("lint/noDoubleEquals".to_string(), Severity::Error),
+			]
+		);
+
+		let mut configuration = RulesConfiguration::default();
+		configuration.set("noDebugger", RuleLevel::Off);
+		configuration.set("noDoubleEquals", RuleLevel::Warn);
+		let analyzer = registry.analyzer(&configuration);
+		assert!(!analyzer.rule_names().any(|name| name == "noDebugger"));
+		let diagnostics = analyzer.analyze(&root, 0);
+		assert_eq!(diagnostics.len(), 1);
+		assert_eq!(diagnostics[0].severity, Severity::Warning);
+	}
+
+	#[test]
+	fn detects_conflicts() {
+		let mut registry = RuleRegistry::new();
+		assert_eq!(
+			registry.register(Box::new(NoDebugger)),
+			Err(RegistrationError { name: "noDebugger" })
+		);
+		assert_eq!(registry.rules().count(), 3);
+	}
+}
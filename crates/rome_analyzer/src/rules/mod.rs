@@ -0,0 +1,25 @@
+//! The rules of the linter, each module contains a rule along with its tests
+mod no_debugger;
+mod no_double_equals;
+mod no_sparse_array;
+
+pub use no_debugger::NoDebugger;
+pub use no_double_equals::NoDoubleEquals;
+pub use no_sparse_array::NoSparseArray;
+
+#[cfg(test)]
+pub(crate) mod test_utils {
+	use crate::{Rule, RuleRegistry, RulesConfiguration};
+	use rslint_errors::Diagnostic;
+	use rslint_parser::parse_module;
+
+	/// Runs only `rule` over the module `code`
+	pub(crate) fn check(rule: impl Rule + 'static, code: &str) -> Vec<Diagnostic> {
+		let mut registry = RuleRegistry::empty();
+		registry.register(Box::new(rule)).unwrap();
+		let root = parse_module(code, 0).syntax();
+		registry
+			.analyzer(&RulesConfiguration::default())
+			.analyze(&root, 0)
+	}
+}
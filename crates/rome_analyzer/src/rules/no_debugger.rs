@@ -0,0 +1,60 @@
+use crate::{Rule, RuleCategory, RuleContext};
+use rslint_errors::Applicability;
+use rslint_parser::{SyntaxKind, SyntaxNode, SyntaxNodeExt};
+
+/// Disallows the `debugger` statements, which are usually left by mistake
+pub struct NoDebugger;
+
+impl Rule for NoDebugger {
+	fn name(&self) -> &'static str {
+		"noDebugger"
+	}
+
+	fn category(&self) -> RuleCategory {
+		RuleCategory::Suspicious
+	}
+
+	fn kinds(&self) -> &'static [SyntaxKind] {
+		&[SyntaxKind::DEBUGGER_STMT]
+	}
+
+	fn check(&self, node: &SyntaxNode, ctx: &mut RuleContext) {
+		let range = node.trimmed_range();
+		let mut diagnostic = ctx
+			.diagnostic("This is an unexpected use of the debugger statement")
+			.primary(range, "");
+
+		// the statement can only be removed from a list of statements, the body of an `if`
+		// statement can't be empty
+		let is_in_list = node
+			.parent()
+			.map_or(false, |parent| parent.kind() == SyntaxKind::LIST);
+		if is_in_list {
+			diagnostic =
+				diagnostic.suggestion_no_code(range, "remove the statement", Applicability::Always);
+		}
+		ctx.report(diagnostic);
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::NoDebugger;
+	use crate::rules::test_utils::check;
+	use rslint_errors::Applicability;
+
+	#[test]
+	fn reports_debugger() {
+		let diagnostics = check(NoDebugger, "function a() {\n\tdebugger;\n}\n");
+		assert_eq!(diagnostics.len(), 1);
+		let suggestion = &diagnostics[0].suggestions[0];
+		assert_eq!(suggestion.span.range, 16..25);
+		assert_eq!(suggestion.applicability, Applicability::Always);
+
+		let diagnostics = check(NoDebugger, "if (a) debugger;");
+		assert_eq!(diagnostics.len(), 1);
+		assert!(diagnostics[0].suggestions.is_empty());
+
+		assert!(check(NoDebugger, "let debug = true;").is_empty());
+	}
+}
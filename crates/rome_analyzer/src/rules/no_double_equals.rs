@@ -0,0 +1,83 @@
+use crate::{Rule, RuleCategory, RuleContext};
+use rslint_errors::Applicability;
+use rslint_parser::ast::{BinExpr, BinOp, Expr};
+use rslint_parser::{AstNode, SyntaxKind, SyntaxNode};
+
+/// Disallows `==` and `!=`, which convert their operands before comparing them.
+///
+/// The comparisons with `null` are allowed, as they match both `null` and `undefined`
+pub struct NoDoubleEquals;
+
+impl Rule for NoDoubleEquals {
+	fn name(&self) -> &'static str {
+		"noDoubleEquals"
+	}
+
+	fn category(&self) -> RuleCategory {
+		RuleCategory::Suspicious
+	}
+
+	fn kinds(&self) -> &'static [SyntaxKind] {
+		&[SyntaxKind::BIN_EXPR]
+	}
+
+	fn check(&self, node: &SyntaxNode, ctx: &mut RuleContext) {
+		let expr = match BinExpr::cast(node.clone()) {
+			Some(expr) => expr,
+			None => return,
+		};
+		let (token, op) = match expr.op_details() {
+			Some(details) => details,
+			None => return,
+		};
+		let (operator, strict_operator) = match op {
+			BinOp::Equality => ("==", "==="),
+			BinOp::Inequality => ("!=", "!=="),
+			_ => return,
+		};
+		if is_null(expr.lhs()) || is_null(expr.rhs()) {
+			return;
+		}
+
+		let diagnostic = ctx
+			.diagnostic(format!("Use {} instead of {}", strict_operator, operator))
+			.primary(
+				token.text_range(),
+				format!("{} converts the operands before comparing them", operator),
+			)
+			// the result changes when the operands have different types
+			.suggestion(
+				token.text_range(),
+				&format!("use {}", strict_operator),
+				strict_operator,
+				Applicability::MaybeIncorrect,
+			);
+		ctx.report(diagnostic);
+	}
+}
+
+fn is_null(expr: Option<Expr>) -> bool {
+	matches!(expr, Some(Expr::Literal(literal)) if literal.is_null())
+}
+
+#[cfg(test)]
+mod test {
+	use super::NoDoubleEquals;
+	use crate::rules::test_utils::check;
+	use rslint_errors::{Applicability, SuggestionChange};
+
+	#[test]
+	fn reports_double_equals() {
+		let diagnostics = check(NoDoubleEquals, "a == b; a != 1; a === b;");
+		assert_eq!(diagnostics.len(), 2);
+		let suggestion = &diagnostics[1].suggestions[0];
+		assert_eq!(suggestion.span.range, 10..12);
+		assert_eq!(
+			suggestion.substitution,
+			SuggestionChange::String("!==".to_string())
+		);
+		assert_eq!(suggestion.applicability, Applicability::MaybeIncorrect);
+
+		assert!(check(NoDoubleEquals, "a == null; null != b;").is_empty());
+	}
+}
@@ -0,0 +1,66 @@
+use crate::{Rule, RuleCategory, RuleContext};
+use rslint_parser::{SyntaxKind, SyntaxNode, SyntaxNodeExt, T};
+
+/// Disallows the holes of the arrays, like `[a, , b]`, which are easily mistaken for `undefined`
+pub struct NoSparseArray;
+
+impl Rule for NoSparseArray {
+	fn name(&self) -> &'static str {
+		"noSparseArray"
+	}
+
+	fn category(&self) -> RuleCategory {
+		RuleCategory::Correctness
+	}
+
+	fn kinds(&self) -> &'static [SyntaxKind] {
+		&[SyntaxKind::ARRAY_EXPR]
+	}
+
+	fn check(&self, node: &SyntaxNode, ctx: &mut RuleContext) {
+		let elements = node
+			.children()
+			.find(|child| child.kind() == SyntaxKind::LIST);
+		let elements = match elements {
+			Some(elements) => elements,
+			None => return,
+		};
+
+		// a comma at the start of the list or after another comma is a hole
+		let mut follows_element = false;
+		let mut has_holes = false;
+		for element in elements.children_with_tokens() {
+			if element.kind().is_trivia() {
+				continue;
+			}
+			if element.kind() == T![,] {
+				has_holes |= !follows_element;
+				follows_element = false;
+			} else {
+				follows_element = true;
+			}
+		}
+
+		if has_holes {
+			let diagnostic = ctx
+				.diagnostic("This array contains holes")
+				.primary(node.trimmed_range(), "")
+				.footer_help("write `undefined` where an element is missing");
+			ctx.report(diagnostic);
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::NoSparseArray;
+	use crate::rules::test_utils::check;
+
+	#[test]
+	fn reports_holes() {
+		assert_eq!(check(NoSparseArray, "[a, , b];").len(), 1);
+		assert_eq!(check(NoSparseArray, "[, a];").len(), 1);
+		assert_eq!(check(NoSparseArray, "[a, b,];").len(), 0);
+		assert_eq!(check(NoSparseArray, "[];").len(), 0);
+	}
+}
@@ -0,0 +1,300 @@
+//! Remembers the files that are already formatted, or that have no diagnostics, so the following
+//! runs can skip them without parsing them.
+//!
+//! The cache stores, for each file, a fingerprint of its content, of the options of the formatter
+//! or of the enabled rules with their level, and of the version of Rome. The entry of a file is
+//! only used when all of them are the same, so changing the file, the configuration or upgrading
+//! Rome invalidates it. The format and lint commands use distinct cache files.
+use rome_analyzer::Analyzer;
+use rome_formatter::{ByteOrderMark, FormatOptions, IndentStyle, LineEnding, QuoteStyle};
+use rome_path::FileSystem;
+use rslint_errors::Severity;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// The directory of the cache, relative to the project, used when `node_modules` exists
+pub(crate) const DEFAULT_CACHE_DIRECTORY: &str = "node_modules/.cache/rome";
+
+/// The cache of the files that are formatted
+pub(crate) const FORMAT_CACHE_FILE_NAME: &str = "format.json";
+
+/// The cache of the files that have no diagnostics
+pub(crate) const LINT_CACHE_FILE_NAME: &str = "lint.json";
+
+const ROME_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// The content of the cache file
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+	version: String,
+	/// The fingerprints of the files, by canonical path
+	files: HashMap<PathBuf, u64>,
+}
+
+#[derive(Debug)]
+pub(crate) struct Cache {
+	path: PathBuf,
+	files: Mutex<HashMap<PathBuf, u64>>,
+}
+
+impl Cache {
+	/// Loads the cache stored in the file `file_name` of `directory`. A missing or invalid cache,
+	/// or a cache written by another version of Rome, is treated as empty
+	pub(crate) fn load(directory: &Path, file_name: &str) -> Self {
+		let path = directory.join(file_name);
+		let files = fs::read_to_string(&path)
+			.ok()
+			.and_then(|content| serde_json::from_str::<CacheFile>(&content).ok())
+			.filter(|file| file.version == ROME_VERSION)
+			.map(|file| file.files)
+			.unwrap_or_default();
+
+		Self {
+			path,
+			files: Mutex::new(files),
+		}
+	}
+
+	/// Returns `true` if `path` is the file where the cache is stored
+	pub(crate) fn is_cache_file(&self, fs: &dyn FileSystem, path: &Path) -> bool {
+		match (fs.canonicalize(path), fs.canonicalize(&self.path)) {
+			(Ok(path), Ok(cache_path)) => path == cache_path,
+			_ => false,
+		}
+	}
+
+	/// Computes the fingerprint of the content of a file formatted with `options`
+	pub(crate) fn format_fingerprint(content: &str, options: &FormatOptions) -> u64 {
+		fingerprint(&options_bytes(options), content)
+	}
+
+	/// Computes the fingerprint of the content of a file linted with the rules of `analyzer`
+	pub(crate) fn lint_fingerprint(content: &str, analyzer: &Analyzer) -> u64 {
+		fingerprint(&rules_bytes(analyzer.rules()), content)
+	}
+
+	/// Returns `true` if the file has been recorded with the same fingerprint, that is with
+	/// the same content and options
+	pub(crate) fn is_cached(&self, path: &Path, fingerprint: u64) -> bool {
+		let files = self.files.lock().unwrap();
+		files.get(path) == Some(&fingerprint)
+	}
+
+	/// Records that the file, with the content that has this fingerprint, is formatted or has
+	/// no diagnostics
+	pub(crate) fn record(&self, path: PathBuf, fingerprint: u64) {
+		self.files.lock().unwrap().insert(path, fingerprint);
+	}
+
+	/// Writes the cache, dropping the files that don't exist anymore
+	pub(crate) fn save(&self) -> io::Result<()> {
+		let mut files = self.files.lock().unwrap();
+		files.retain(|path, _| path.is_file());
+
+		let file = CacheFile {
+			version: ROME_VERSION.to_string(),
+			files: files.clone(),
+		};
+		if let Some(directory) = self.path.parent() {
+			fs::create_dir_all(directory)?;
+		}
+		fs::write(&self.path, serde_json::to_string(&file)?)
+	}
+}
+
+fn fingerprint(settings: &[u8], content: &str) -> u64 {
+	[ROME_VERSION.as_bytes(), settings, content.as_bytes()]
+		.iter()
+		.fold(FNV_OFFSET_BASIS, |hash, part| {
+			// the length separates the parts, so moving bytes from a part to the next changes the hash
+			let hash = fnv1a(hash, &(part.len() as u64).to_le_bytes());
+			fnv1a(hash, part)
+		})
+}
+
+/// Encodes the options with a stable format. The options are destructured, so adding an option
+/// doesn't compile until it's part of the fingerprint
+fn options_bytes(options: &FormatOptions) -> Vec<u8> {
+	let FormatOptions {
+		indent_style,
+		tab_width,
+		line_width,
+		quote_style,
+		line_ending,
+		byte_order_mark,
+	} = options;
+
+	let (indent_style, indent_size) = match indent_style {
+		IndentStyle::Tab => (0, 0),
+		IndentStyle::Space(size) => (1, *size),
+	};
+	let [line_width_low, line_width_high] = line_width.to_le_bytes();
+	let quote_style = match quote_style {
+		QuoteStyle::Double => 0,
+		QuoteStyle::Single => 1,
+	};
+	let line_ending = match line_ending {
+		LineEnding::LineFeed => 0,
+		LineEnding::CarriageReturnLineFeed => 1,
+		LineEnding::CarriageReturn => 2,
+	};
+	let byte_order_mark = match byte_order_mark {
+		ByteOrderMark::Keep => 0,
+		ByteOrderMark::Remove => 1,
+	};
+
+	vec![
+		indent_style,
+		indent_size,
+		*tab_width,
+		line_width_low,
+		line_width_high,
+		quote_style,
+		line_ending,
+		byte_order_mark,
+	]
+}
+
+/// Encodes the names of the rules and their severity with a stable format. The rules are sorted,
+/// so the order in which they are enabled doesn't matter
+fn rules_bytes<'a>(rules: impl Iterator<Item = (&'a str, Severity)>) -> Vec<u8> {
+	let mut rules: Vec<_> = rules.collect();
+	rules.sort_by_key(|(name, _)| *name);
+
+	let mut bytes = Vec::new();
+	for (name, severity) in rules {
+		bytes.extend_from_slice(&(name.len() as u64).to_le_bytes());
+		bytes.extend_from_slice(name.as_bytes());
+		bytes.push(match severity {
+			Severity::Bug => 0,
+			Severity::Error => 1,
+			Severity::Warning => 2,
+			Severity::Note => 3,
+			Severity::Help => 4,
+		});
+	}
+	bytes
+}
+
+/// The [FNV-1a](http://www.isthe.com/chongo/tech/comp/fnv/) hash is used instead of the hasher
+/// of the standard library, because its output must be the same across builds
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+	bytes.iter().fold(hash, |hash, byte| {
+		(hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+	})
+}
+
+#[cfg(test)]
+mod test {
+	use super::{options_bytes, rules_bytes, Cache, FORMAT_CACHE_FILE_NAME, LINT_CACHE_FILE_NAME};
+	use rome_analyzer::{RuleLevel, RuleRegistry, RulesConfiguration};
+	use rome_formatter::{FormatOptions, IndentStyle, QuoteStyle};
+	use rome_path::testing::TestDirectory;
+	use rome_path::MemoryFileSystem;
+	use rslint_errors::Severity;
+	use std::fs;
+	use std::path::Path;
+
+	#[test]
+	fn invalidates_entries() {
+		let directory = TestDirectory::new("cache_invalidates_entries");
+		let file = directory.join("index.js");
+		fs::write(&file, "let a = 1;\n").unwrap();
+
+		let options = FormatOptions::default();
+		let fingerprint = Cache::format_fingerprint("let a = 1;\n", &options);
+		let cache = Cache::load(&directory.join("cache"), FORMAT_CACHE_FILE_NAME);
+		assert!(!cache.is_cached(&file, fingerprint));
+		cache.record(file.clone(), fingerprint);
+		cache.save().unwrap();
+
+		let cache = Cache::load(&directory.join("cache"), FORMAT_CACHE_FILE_NAME);
+		assert!(cache.is_cached(&file, fingerprint));
+		assert!(!cache.is_cached(&file, Cache::format_fingerprint("let a = 2;\n", &options)));
+		let options = FormatOptions {
+			quote_style: QuoteStyle::Single,
+			..FormatOptions::default()
+		};
+		assert!(!cache.is_cached(&file, Cache::format_fingerprint("let a = 1;\n", &options)));
+
+		// the lint command has its own cache
+		let cache = Cache::load(&directory.join("cache"), LINT_CACHE_FILE_NAME);
+		assert!(!cache.is_cached(&file, fingerprint));
+	}
+
+	#[test]
+	fn finds_cache_file() {
+		let fs = MemoryFileSystem::default()
+			.with_file("/project/.cache/format.json", "{}")
+			.with_file("/project/index.js", "");
+		// the cache is read from the disk, where the directory doesn't exist
+		let cache = Cache::load(Path::new("/project/.cache"), FORMAT_CACHE_FILE_NAME);
+		assert!(cache.is_cache_file(&fs, Path::new("/project/./.cache/format.json")));
+		assert!(!cache.is_cache_file(&fs, Path::new("/project/index.js")));
+	}
+
+	#[test]
+	fn invalidates_lint_entries() {
+		let registry = RuleRegistry::new();
+		let mut rules = RulesConfiguration::default();
+		let analyzer = registry.analyzer(&rules);
+		let fingerprint = Cache::lint_fingerprint("let a = 1;\n", &analyzer);
+		assert_eq!(
+			fingerprint,
+			Cache::lint_fingerprint("let a = 1;\n", &registry.analyzer(&rules))
+		);
+
+		rules.set("noDoubleEquals", RuleLevel::Warn);
+		let analyzer = registry.analyzer(&rules);
+		assert_ne!(
+			Cache::lint_fingerprint("let a = 1;\n", &analyzer),
+			fingerprint
+		);
+		rules.set("noDoubleEquals", RuleLevel::Off);
+		let analyzer = registry.analyzer(&rules);
+		assert_ne!(
+			Cache::lint_fingerprint("let a = 1;\n", &analyzer),
+			fingerprint
+		);
+	}
+
+	#[test]
+	fn encodes_options() {
+		// changing the encoding invalidates the caches of the users
+		assert_eq!(
+			options_bytes(&FormatOptions::default()),
+			vec![0, 0, 2, 80, 0, 0, 0, 0]
+		);
+		let options = FormatOptions {
+			indent_style: IndentStyle::Space(4),
+			line_width: 120,
+			..FormatOptions::default()
+		};
+		assert_eq!(options_bytes(&options), vec![1, 4, 2, 120, 0, 0, 0, 0]);
+	}
+
+	#[test]
+	fn encodes_rules() {
+		let rules = vec![
+			("noDebugger", Severity::Error),
+			("noEval", Severity::Warning),
+		];
+		let mut expected = vec![10, 0, 0, 0, 0, 0, 0, 0];
+		expected.extend_from_slice(b"noDebugger");
+		expected.push(1);
+		expected.extend_from_slice(&[6, 0, 0, 0, 0, 0, 0, 0]);
+		expected.extend_from_slice(b"noEval");
+		expected.push(2);
+
+		assert_eq!(rules_bytes(rules.iter().copied()), expected);
+		// the order of the rules doesn't matter
+		assert_eq!(rules_bytes(rules.iter().rev().copied()), expected);
+	}
+}
@@ -0,0 +1,690 @@
+use crate::cache::{Cache, FORMAT_CACHE_FILE_NAME};
+use crate::commands::{
+	cache_directory, load_project_configuration, EXIT_CHECK_FAILED, EXIT_FAILURE, EXIT_INTERRUPTED,
+};
+use crate::configuration::{Configuration, FormatterConfiguration};
+use crate::diff::unified_diff;
+use crate::editorconfig::EditorConfig;
+use crate::git::Repository;
+use crate::reporter::{create_reporter, Mode, Reporter, Status, Summary};
+use crate::runner::{is_interrupted, process_files, RunStatus};
+use crate::traversal::{collect_files, input_directory, TraversalOptions};
+use crate::watch::WatchSession;
+use clap::ArgMatches;
+use rome_core::{create_app, App, Workspace};
+use rome_formatter::FormatOptions;
+use rome_path::{
+	check_file, format_file_and_save, format_source, FileStatus, FileSystem, FormatFileError,
+	OsFileSystem, RomePath,
+};
+use rslint_errors::file::SimpleFile;
+use rslint_errors::{Diagnostic, Emitter};
+use std::collections::HashSet;
+use std::env;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// What happened to a single file
+enum FileOutcome {
+	Formatted,
+	Unchanged,
+	/// The file can't be formatted
+	Skipped,
+	/// Check mode only, the content of the file differs from the formatted code
+	NotFormatted {
+		source: String,
+		formatted: String,
+	},
+	/// The run has been interrupted before formatting the file
+	Interrupted,
+	/// The file couldn't be processed, the diagnostic explains why
+	Failed(Diagnostic),
+}
+
+/// The options of the formatter, coming from the EditorConfig files, the configuration file
+/// and the arguments
+#[derive(Default)]
+pub(crate) struct FormatSettings {
+	pub(crate) editorconfig: EditorConfig,
+	pub(crate) configuration: Configuration,
+	pub(crate) arguments: FormatterConfiguration,
+}
+
+impl FormatSettings {
+	/// Resolves the options used to format the file. The arguments take precedence over the
+	/// configuration file, which takes precedence over the EditorConfig files
+	pub(crate) fn resolve(&self, rome_path: &RomePath) -> FormatterConfiguration {
+		let language = rome_path.get_handler().map(|handler| handler.language());
+		let mut formatter = self.editorconfig.resolve(rome_path);
+		formatter.merge(&self.configuration.formatter_for(language));
+		formatter.merge(&self.arguments);
+		formatter
+	}
+
+	fn options(&self, rome_path: &RomePath) -> FormatOptions {
+		self.resolve(rome_path).to_format_options()
+	}
+}
+
+/// Reads the options of the formatter passed as arguments, the values
+/// have already been validated
+fn formatter_arguments(matches: &ArgMatches) -> FormatterConfiguration {
+	FormatterConfiguration {
+		indent_style: matches
+			.value_of("indent_style")
+			.map(|style| style.parse().unwrap()),
+		indent_size: matches
+			.value_of("indent_size")
+			.map(|size| size.parse().unwrap()),
+		line_width: matches
+			.value_of("line_width")
+			.map(|width| width.parse().unwrap()),
+		quote_style: matches
+			.value_of("quote_style")
+			.map(|style| style.parse().unwrap()),
+		line_ending: matches
+			.value_of("line_ending")
+			.map(|ending| ending.parse().unwrap()),
+		byte_order_mark: matches
+			.value_of("byte_order_mark")
+			.map(|byte_order_mark| byte_order_mark.parse().unwrap()),
+		..FormatterConfiguration::default()
+	}
+}
+
+/// Handler for the `format` command
+pub(crate) fn format(matches: &ArgMatches) {
+	let respect_ignore = !matches.is_present("no_ignore");
+	let is_verbose = matches.is_present("verbose");
+	// the value has already been validated
+	let reporter_kind = matches
+		.value_of("reporter")
+		.map(|kind| kind.parse().unwrap())
+		.unwrap_or_default();
+
+	let fs = OsFileSystem;
+	let loaded_configuration = load_project_configuration();
+	let project_directory = loaded_configuration
+		.as_ref()
+		.map(|loaded| loaded.root().to_path_buf());
+	let files_filter = loaded_configuration
+		.as_ref()
+		.map(|loaded| loaded.files_filter(&fs, respect_ignore));
+	let settings = FormatSettings {
+		editorconfig: EditorConfig::default(),
+		configuration: loaded_configuration
+			.map(|loaded| loaded.configuration)
+			.unwrap_or_default(),
+		arguments: formatter_arguments(matches),
+	};
+
+	if let Some(path) = matches.value_of("stdin_file_path") {
+		format_stdin(path, &settings);
+		return;
+	}
+
+	let is_check = matches.is_present("check");
+	// the value has already been validated
+	let threads = matches
+		.value_of("threads")
+		.map(|threads| threads.parse::<usize>().unwrap());
+
+	let inputs: Vec<&str> = matches.values_of("input").into_iter().flatten().collect();
+	let options = TraversalOptions {
+		respect_ignore_files: respect_ignore,
+		collect_ignored: is_verbose,
+		filter: files_filter.as_ref(),
+	};
+	let collected = match collect_files(&fs, inputs.iter().copied(), &options) {
+		Ok(collected) => collected,
+		Err(message) => {
+			eprintln!("{}", message);
+			std::process::exit(EXIT_FAILURE);
+		}
+	};
+
+	let mode = if is_check { Mode::Check } else { Mode::Format };
+	let mut reporter = create_reporter(reporter_kind, mode, is_verbose, false);
+	for path in &collected.ignored {
+		reporter.report_ignored(path);
+	}
+
+	let is_staged = matches.is_present("staged");
+	let repository = if is_staged || matches.is_present("changed") {
+		match Repository::discover() {
+			Ok(repository) => Some(repository),
+			Err(message) => {
+				eprintln!("{}", message);
+				std::process::exit(EXIT_FAILURE);
+			}
+		}
+	} else {
+		None
+	};
+	let mut files = collected.files;
+	if let Some(repository) = &repository {
+		let selected = if is_staged {
+			repository.staged_files()
+		} else {
+			// the value defaults to HEAD
+			repository.changed_files(matches.value_of("changed").unwrap())
+		};
+		let selected: HashSet<_> = match selected {
+			Ok(selected) => selected.into_iter().collect(),
+			Err(message) => {
+				eprintln!("{}", message);
+				std::process::exit(EXIT_FAILURE);
+			}
+		};
+		files.retain(|path| {
+			fs.canonicalize(path)
+				.map_or(false, |path| selected.contains(&path))
+		});
+	}
+
+	let cache = if matches.is_present("no_cache") || is_staged {
+		None
+	} else {
+		cache_directory(matches, project_directory.as_deref())
+			.map(|directory| Cache::load(&directory, FORMAT_CACHE_FILE_NAME))
+	};
+
+	let app = create_app();
+	let runner = FormatRunner {
+		fs: &fs,
+		app: &app,
+		settings: &settings,
+		is_check,
+		threads,
+		staged: if is_staged { repository.as_ref() } else { None },
+		cache: cache.as_ref(),
+	};
+	let result = runner.run(&files, reporter.as_mut());
+
+	if matches.is_present("watch") && result.status == RunStatus::Completed {
+		let directories = watched_directories(&fs, project_directory.as_deref(), &inputs);
+		let options = TraversalOptions {
+			collect_ignored: false,
+			..options
+		};
+		// the changed files are listed, as only a few files are processed each time
+		let mut reporter = create_reporter(reporter_kind, mode, is_verbose, true);
+		watch(
+			&fs,
+			&directories,
+			&inputs,
+			&options,
+			&result.written,
+			|files| runner.run(files, reporter.as_mut()),
+		);
+		return;
+	}
+
+	if result.status == RunStatus::Interrupted {
+		eprintln!("The process has been interrupted, some files haven't been processed");
+		std::process::exit(EXIT_INTERRUPTED);
+	}
+	if result.summary.failed > 0 {
+		std::process::exit(EXIT_FAILURE);
+	}
+	if is_check && result.summary.formatted > 0 {
+		std::process::exit(EXIT_CHECK_FAILED);
+	}
+}
+
+/// What happened during a run of the formatter over a list of files
+struct RunResult {
+	summary: Summary,
+	status: RunStatus,
+	/// The files that have been written by the formatter
+	written: Vec<PathBuf>,
+}
+
+/// Formats, or checks, lists of files with the same settings
+struct FormatRunner<'a> {
+	fs: &'a dyn FileSystem,
+	app: &'a App,
+	settings: &'a FormatSettings,
+	is_check: bool,
+	threads: Option<usize>,
+	/// When set, the staged content of the files is processed instead of the working tree
+	staged: Option<&'a Repository>,
+	cache: Option<&'a Cache>,
+}
+
+impl FormatRunner<'_> {
+	fn run(&self, files: &[PathBuf], reporter: &mut dyn Reporter) -> RunResult {
+		let mut summary = Summary::default();
+		let mut workspace = Workspace::new();
+		let mut written = Vec::new();
+
+		// the cache is written by the runner itself
+		let files: Vec<_> = match self.cache {
+			Some(cache) => files
+				.iter()
+				.filter(|path| !cache.is_cache_file(self.fs, path))
+				.cloned()
+				.collect(),
+			None => files.to_vec(),
+		};
+
+		let status = process_files(
+			&files,
+			self.threads,
+			|path| {
+				let start = Instant::now();
+				let outcome = match self.staged {
+					Some(repository) => format_staged(
+						path,
+						repository,
+						self.fs,
+						self.app,
+						self.settings,
+						self.is_check,
+					),
+					None => self.process_path(path),
+				};
+				(outcome, start.elapsed())
+			},
+			|path, (outcome, duration)| {
+				if let FileOutcome::Formatted = outcome {
+					written.push(path.to_path_buf());
+				}
+				report(
+					path,
+					outcome,
+					duration,
+					&mut summary,
+					&mut workspace,
+					reporter,
+				)
+			},
+		);
+
+		reporter.report_summary(&summary);
+
+		if let Some(cache) = self.cache {
+			if let Err(error) = cache.save() {
+				eprintln!("Cannot write the cache: {}", error);
+			}
+		}
+
+		RunResult {
+			summary,
+			status,
+			written,
+		}
+	}
+
+	/// Formats, or checks, a file of the working tree. The files that the cache knows to be
+	/// formatted are skipped without parsing them
+	fn process_path(&self, path: &Path) -> FileOutcome {
+		let cached = self.cache.and_then(|cache| {
+			let rome_path = formattable_path(path, self.fs, self.app)?;
+			let options = self.settings.options(&rome_path);
+			Some((cache, self.fs.canonicalize(path).ok()?, options))
+		});
+		let (cache, canonical_path, options) = match cached {
+			Some(cached) => cached,
+			None if self.is_check => return check_path(path, self.fs, self.app, self.settings),
+			None => return format_path(path, self.fs, self.app, self.settings),
+		};
+
+		let fingerprint = |path: &Path| {
+			RomePath::new(path)
+				.with_file_system(self.fs)
+				.read_to_string()
+				.ok()
+				.map(|content| Cache::format_fingerprint(&content, &options))
+		};
+		let source_fingerprint = fingerprint(path);
+		if let Some(fingerprint) = source_fingerprint {
+			if cache.is_cached(&canonical_path, fingerprint) {
+				return FileOutcome::Unchanged;
+			}
+		}
+
+		let outcome = if self.is_check {
+			check_path(path, self.fs, self.app, self.settings)
+		} else {
+			format_path(path, self.fs, self.app, self.settings)
+		};
+		let formatted_fingerprint = match outcome {
+			FileOutcome::Unchanged => source_fingerprint,
+			FileOutcome::Formatted => fingerprint(path),
+			_ => None,
+		};
+		if let Some(fingerprint) = formatted_fingerprint {
+			cache.record(canonical_path, fingerprint);
+		}
+		outcome
+	}
+}
+
+/// The directories to watch: the directory of the project, along with the directories
+/// of the inputs that are outside of it
+fn watched_directories(
+	fs: &dyn FileSystem,
+	project_directory: Option<&Path>,
+	inputs: &[&str],
+) -> Vec<PathBuf> {
+	let working_directory = env::current_dir().ok();
+	let project_directory = project_directory.or(working_directory.as_deref());
+
+	let mut directories: Vec<_> = project_directory
+		.into_iter()
+		.chain(inputs.iter().map(|input| input_directory(fs, input)))
+		.filter_map(|directory| fs.canonicalize(directory).ok())
+		.collect();
+	// the parents are sorted before their subdirectories, which are already watched
+	directories.sort();
+	directories.dedup_by(|directory, parent| directory.starts_with(parent));
+	directories
+}
+
+/// Processes again the files matched by the inputs each time they change, until the user stops
+/// the process. `written` are the files that the formatter has already written
+fn watch(
+	fs: &dyn FileSystem,
+	directories: &[PathBuf],
+	inputs: &[&str],
+	options: &TraversalOptions,
+	written: &[PathBuf],
+	mut run: impl FnMut(&[PathBuf]) -> RunResult,
+) {
+	let mut session = match WatchSession::new(fs, directories) {
+		Ok(session) => session,
+		Err(message) => {
+			eprintln!("{}", message);
+			std::process::exit(EXIT_FAILURE);
+		}
+	};
+	for path in written {
+		session.record_write(path);
+	}
+
+	eprintln!("Watching for changes, press Ctrl-C to stop");
+	while let Some(changes) = session.next_changes() {
+		// the inputs are resolved again, as files may have been added or removed
+		let collected = match collect_files(fs, inputs.iter().copied(), options) {
+			Ok(collected) => collected,
+			Err(message) => {
+				eprintln!("{}", message);
+				continue;
+			}
+		};
+		let files: Vec<_> = collected
+			.files
+			.into_iter()
+			.filter(|path| {
+				fs.canonicalize(path)
+					.map_or(false, |path| changes.binary_search(&path).is_ok())
+			})
+			.collect();
+
+		if !files.is_empty() {
+			let result = run(&files);
+			for path in &result.written {
+				session.record_write(path);
+			}
+		}
+	}
+}
+
+/// Formats the source read from stdin and prints the result on stdout.
+///
+/// The path is only used to deduce the language of the source, along with the source itself
+/// when the path doesn't have an extension.
+fn format_stdin(path: &str, settings: &FormatSettings) {
+	let mut source = String::new();
+	if let Err(error) = io::stdin().read_to_string(&mut source) {
+		eprintln!("Cannot read the source from stdin: {}", error);
+		std::process::exit(EXIT_FAILURE);
+	}
+
+	let app = create_app();
+	let rome_path = RomePath::new(path).deduce_handler_with_content(&app, &source);
+	let options = settings.options(&rome_path);
+
+	match format_source(&rome_path, &source, options) {
+		Ok(formatted) => print!("{}", formatted.code()),
+		Err(error) => {
+			let file = SimpleFile::new(path.to_string(), source);
+			let diagnostic = error_diagnostic(Path::new(path), &error.into());
+			Emitter::new(&file)
+				.emit_stderr(&diagnostic, atty::is(atty::Stream::Stderr))
+				.expect("cannot print the diagnostic");
+			std::process::exit(EXIT_FAILURE);
+		}
+	}
+}
+
+/// Creates the diagnostic of an error that prevented the file from being formatted
+fn error_diagnostic(path: &Path, error: &FormatFileError) -> Diagnostic {
+	match error {
+		FormatFileError::File(error) => error.to_diagnostic(0, path),
+		FormatFileError::Format(error) => Diagnostic::error(
+			0,
+			"format",
+			format!("Cannot format \"{}\": {}", path.display(), error),
+		),
+	}
+}
+
+/// Creates the [RomePath] of the file, if the file can be formatted
+fn formattable_path<'a>(path: &Path, fs: &'a dyn FileSystem, app: &'a App) -> Option<RomePath<'a>> {
+	let rome_path = RomePath::new(path).with_file_system(fs).deduce_handler(app);
+	let can_format = rome_path
+		.get_handler()
+		.map_or(false, |handler| handler.capabilities().format);
+
+	if can_format {
+		Some(rome_path)
+	} else {
+		None
+	}
+}
+
+fn format_path(
+	path: &Path,
+	fs: &dyn FileSystem,
+	app: &App,
+	settings: &FormatSettings,
+) -> FileOutcome {
+	let mut rome_path = match formattable_path(path, fs, app) {
+		Some(rome_path) => rome_path,
+		None => return FileOutcome::Skipped,
+	};
+	let options = settings.options(&rome_path);
+
+	// the file is checked right before writing it, a file that has been started is always completed
+	if is_interrupted() {
+		return FileOutcome::Interrupted;
+	}
+
+	match format_file_and_save(&mut rome_path, options) {
+		Ok(FileStatus::Formatted) => FileOutcome::Formatted,
+		Ok(FileStatus::Unchanged) => FileOutcome::Unchanged,
+		Err(error) => FileOutcome::Failed(error_diagnostic(path, &error)),
+	}
+}
+
+/// Formats the file without saving it
+fn check_path(
+	path: &Path,
+	fs: &dyn FileSystem,
+	app: &App,
+	settings: &FormatSettings,
+) -> FileOutcome {
+	let mut rome_path = match formattable_path(path, fs, app) {
+		Some(rome_path) => rome_path,
+		None => return FileOutcome::Skipped,
+	};
+	let options = settings.options(&rome_path);
+
+	match check_file(&mut rome_path, options) {
+		Ok(result) if result.is_formatted() => FileOutcome::Unchanged,
+		Ok(result) => FileOutcome::NotFormatted {
+			formatted: result.formatted.code().clone(),
+			source: result.source,
+		},
+		Err(error) => FileOutcome::Failed(error_diagnostic(path, &error)),
+	}
+}
+
+/// Formats the staged content of the file and stages the result. The file in the working tree is
+/// updated only when it doesn't have unstaged changes, so they are never overwritten
+fn format_staged(
+	path: &Path,
+	repository: &Repository,
+	fs: &dyn FileSystem,
+	app: &App,
+	settings: &FormatSettings,
+	is_check: bool,
+) -> FileOutcome {
+	let mut rome_path = match formattable_path(path, fs, app) {
+		Some(rome_path) => rome_path,
+		None => return FileOutcome::Skipped,
+	};
+	let options = settings.options(&rome_path);
+
+	let source = match repository.read_staged(path) {
+		Ok(source) => source,
+		Err(message) => return FileOutcome::Failed(Diagnostic::error(0, "git", message)),
+	};
+	let formatted = match format_source(&rome_path, &source, options) {
+		Ok(formatted) => formatted.code().clone(),
+		Err(error) => return FileOutcome::Failed(error_diagnostic(path, &error.into())),
+	};
+
+	if formatted == source {
+		return FileOutcome::Unchanged;
+	}
+	if is_check {
+		return FileOutcome::NotFormatted { source, formatted };
+	}
+	if is_interrupted() {
+		return FileOutcome::Interrupted;
+	}
+
+	let is_fully_staged = rome_path.read_to_string().as_ref() == Ok(&source);
+	if let Err(message) = repository.stage(path, &formatted) {
+		return FileOutcome::Failed(Diagnostic::error(0, "git", message));
+	}
+	if is_fully_staged {
+		if let Err(error) = rome_path.save(&formatted) {
+			return FileOutcome::Failed(error.to_diagnostic(0, path));
+		}
+	}
+	FileOutcome::Formatted
+}
+
+/// Updates the summary with the outcome of a file and reports it. In check mode, the difference
+/// between the content of the file and the formatted code is a note of the diagnostic of the file.
+///
+/// The files with a diagnostic are added to `workspace`, which the diagnostic points to
+fn report(
+	path: &Path,
+	outcome: FileOutcome,
+	duration: Duration,
+	summary: &mut Summary,
+	workspace: &mut Workspace,
+	reporter: &mut dyn Reporter,
+) {
+	match outcome {
+		FileOutcome::Formatted => {
+			summary.formatted += 1;
+			reporter.report_file(path, Status::Formatted, duration);
+		}
+		FileOutcome::Unchanged => {
+			summary.unchanged += 1;
+			reporter.report_file(path, Status::Unchanged, duration);
+		}
+		FileOutcome::Skipped => {
+			summary.skipped += 1;
+			reporter.report_file(path, Status::Skipped, duration);
+		}
+		FileOutcome::Interrupted => {}
+		FileOutcome::Failed(mut diagnostic) => {
+			summary.failed += 1;
+
+			// the content of the file isn't needed, the diagnostic doesn't point to it
+			diagnostic.file_id = workspace.insert(path, String::new());
+			reporter.report_diagnostic(workspace, &diagnostic);
+			reporter.report_file(path, Status::Failed(&diagnostic.title), duration);
+		}
+		FileOutcome::NotFormatted { source, formatted } => {
+			summary.formatted += 1;
+
+			let diff = unified_diff(&path.display().to_string(), &source, &formatted);
+			let file_id = workspace.insert(path, source);
+			let diagnostic = Diagnostic::error(
+				file_id,
+				"format",
+				format!("The file \"{}\" is not formatted", path.display()),
+			);
+			reporter.report_not_formatted(workspace, &diagnostic, &diff);
+			reporter.report_file(path, Status::NotFormatted, duration);
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::{FormatRunner, FormatSettings};
+	use crate::reporter::{JsonReporter, Mode};
+	use crate::traversal::{collect_files, TraversalOptions};
+	use rome_core::create_app;
+	use rome_path::{FileSystem, MemoryFileSystem};
+	use std::io;
+	use std::path::Path;
+
+	#[test]
+	fn formats_in_memory() {
+		let fs = MemoryFileSystem::default()
+			.with_file("src/index.js", "if (a) {\nlet b  =  1\n}")
+			.with_file("src/index.min.js", "let b  =  2")
+			.with_file("bin/deploy", "#!/usr/bin/env node\nlet c = 3;\n")
+			.with_file(
+				".editorconfig",
+				"[*]\nindent_style = space\nindent_size = 4\n",
+			)
+			.with_file(".romeignore", "*.min.js\n");
+		let options = TraversalOptions {
+			respect_ignore_files: true,
+			..TraversalOptions::default()
+		};
+		let collected = collect_files(&fs, vec!["."], &options).unwrap();
+		assert_eq!(collected.files.len(), 4);
+
+		let app = create_app();
+		let runner = FormatRunner {
+			fs: &fs,
+			app: &app,
+			settings: &FormatSettings::default(),
+			is_check: false,
+			threads: Some(1),
+			staged: None,
+			cache: None,
+		};
+		let result = runner.run(
+			&collected.files,
+			&mut JsonReporter::new(io::sink(), Mode::Format),
+		);
+
+		assert_eq!(result.summary.formatted, 1);
+		assert_eq!(result.summary.unchanged, 1);
+		assert_eq!(result.summary.skipped, 2);
+		assert_eq!(result.written, vec![Path::new("./src/index.js")]);
+		// the block is indented with the options of the .editorconfig file, rather than with tabs
+		assert_eq!(
+			fs.read(Path::new("src/index.js")).unwrap(),
+			b"if (a) {\n    let b = 1;\n}\n"
+		);
+		assert_eq!(
+			fs.read(Path::new("src/index.min.js")).unwrap(),
+			b"let b  =  2"
+		);
+	}
+}
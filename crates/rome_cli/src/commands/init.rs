@@ -0,0 +1,317 @@
+use crate::commands::EXIT_FAILURE;
+use crate::configuration::{FormatterConfiguration, CONFIGURATION_FILE_NAME};
+use crate::editorconfig::{EditorConfigFile, EDITORCONFIG_FILE_NAME};
+use clap::ArgMatches;
+use rome_formatter::{ByteOrderMark, IndentStyle, LineEnding, QuoteStyle};
+use rome_path::{FileSystem, OsFileSystem, RomePath};
+use rslint_parser::ast::{Expr, LiteralKind, ObjectProp, PropName};
+use rslint_parser::parse_expr;
+use std::convert::TryFrom;
+use std::fs;
+use std::path::Path;
+
+/// Only the JSON variants of the Prettier configuration are supported
+const PRETTIER_FILE_NAMES: &[&str] = &[".prettierrc", ".prettierrc.json"];
+
+/// Handler for the `init` command
+pub(crate) fn init(matches: &ArgMatches) {
+	let directory = Path::new(".");
+	let path = directory.join(CONFIGURATION_FILE_NAME);
+
+	if path.exists() && !matches.is_present("force") {
+		eprintln!(
+			"The configuration file \"{}\" already exists, use --force to overwrite it",
+			CONFIGURATION_FILE_NAME
+		);
+		std::process::exit(EXIT_FAILURE);
+	}
+
+	let (formatter, sources) = detect_formatter_configuration(&OsFileSystem, directory);
+	if let Err(error) = fs::write(&path, configuration_template(&formatter)) {
+		eprintln!(
+			"Cannot write the configuration file \"{}\": {}",
+			CONFIGURATION_FILE_NAME, error
+		);
+		std::process::exit(EXIT_FAILURE);
+	}
+
+	if sources.is_empty() {
+		println!("Created {}", CONFIGURATION_FILE_NAME);
+	} else {
+		println!(
+			"Created {}, using the options of {}",
+			CONFIGURATION_FILE_NAME,
+			sources.join(" and ")
+		);
+	}
+}
+
+/// Reads the options of the formatter from the configuration files of other tools, returning them
+/// with the names of the files that have been used.
+///
+/// The options of Prettier take precedence, as Prettier itself reads the `.editorconfig` file
+/// and overrides its options
+fn detect_formatter_configuration(
+	fs: &dyn FileSystem,
+	directory: &Path,
+) -> (FormatterConfiguration, Vec<&'static str>) {
+	let read_to_string = |file_name| {
+		RomePath::new(directory.join(file_name))
+			.with_file_system(fs)
+			.read_to_string()
+	};
+	let mut formatter = FormatterConfiguration::default();
+	let mut sources = Vec::new();
+
+	if let Ok(content) = read_to_string(EDITORCONFIG_FILE_NAME) {
+		// the options that apply to a JavaScript file at the root of the project
+		let editorconfig = EditorConfigFile::parse(&content).resolve(Path::new("index.js"));
+		formatter.merge(&editorconfig);
+		sources.push(EDITORCONFIG_FILE_NAME);
+	}
+
+	for file_name in PRETTIER_FILE_NAMES.iter().copied() {
+		if let Ok(content) = read_to_string(file_name) {
+			match read_prettier_configuration(&content) {
+				Some(prettier) => {
+					formatter.merge(&prettier);
+					// Prettier indents with spaces by default, which only applies when
+					// the `.editorconfig` file doesn't set the style either
+					if let (None, Some(size)) = (&formatter.indent_style, prettier.indent_size) {
+						formatter.indent_style = Some(IndentStyle::Space(size));
+					}
+					sources.push(file_name);
+				}
+				None => eprintln!(
+					"Ignoring \"{}\", only the JSON configurations of Prettier are supported",
+					file_name
+				),
+			}
+			break;
+		}
+	}
+
+	(formatter, sources)
+}
+
+/// Reads the options of a Prettier configuration written in JSON, returns [None] if
+/// the content isn't a JSON object
+fn read_prettier_configuration(content: &str) -> Option<FormatterConfiguration> {
+	let parse = parse_expr(content, 0);
+	if !parse.errors().is_empty() {
+		return None;
+	}
+	let object = match parse.try_tree()? {
+		Expr::ObjectExpr(object) => object,
+		_ => return None,
+	};
+
+	let mut formatter = FormatterConfiguration::default();
+	let mut use_tabs = None;
+	for prop in object.props().iter() {
+		let (key, value) = match prop {
+			ObjectProp::LiteralProp(prop) => match (prop.key(), prop.value()) {
+				(Ok(PropName::Literal(key)), Ok(Expr::Literal(value))) => (key, value),
+				_ => continue,
+			},
+			_ => continue,
+		};
+		let key = match key.inner_string_text() {
+			Some(key) => key.to_string(),
+			None => continue,
+		};
+
+		match (key.as_str(), value.kind()) {
+			("useTabs", LiteralKind::Bool(tabs)) => use_tabs = Some(tabs),
+			("tabWidth", LiteralKind::Number(width)) => formatter.indent_size = integer(width),
+			("printWidth", LiteralKind::Number(width)) => formatter.line_width = integer(width),
+			("singleQuote", LiteralKind::Bool(single_quote)) => {
+				formatter.quote_style = Some(if single_quote {
+					QuoteStyle::Single
+				} else {
+					QuoteStyle::Double
+				})
+			}
+			("endOfLine", LiteralKind::String) => {
+				formatter.line_ending = value
+					.inner_string_text()
+					.and_then(|text| text.to_string().parse().ok())
+			}
+			_ => {}
+		}
+	}
+
+	// without `useTabs`, the style is left to the `.editorconfig` file
+	formatter.indent_style = use_tabs.map(|use_tabs| {
+		if use_tabs {
+			IndentStyle::Tab
+		} else {
+			IndentStyle::Space(formatter.indent_size.unwrap_or(2))
+		}
+	});
+
+	Some(formatter)
+}
+
+fn integer<T: TryFrom<u64>>(number: f64) -> Option<T> {
+	if number.fract() == 0.0 && number > 0.0 {
+		T::try_from(number as u64).ok()
+	} else {
+		None
+	}
+}
+
+/// Creates the content of the configuration file, where the options of the formatter
+/// are seeded from `formatter`
+fn configuration_template(formatter: &FormatterConfiguration) -> String {
+	let options = formatter.to_format_options();
+	let (indent_style, indent_size) = match options.indent_style {
+		IndentStyle::Tab => ("tab", formatter.indent_size.unwrap_or(2)),
+		IndentStyle::Space(size) => ("space", size),
+	};
+	let quote_style = match options.quote_style {
+		QuoteStyle::Double => "double",
+		QuoteStyle::Single => "single",
+	};
+	let line_ending = match options.line_ending {
+		LineEnding::LineFeed => "lf",
+		LineEnding::CarriageReturnLineFeed => "crlf",
+		LineEnding::CarriageReturn => "cr",
+	};
+	let byte_order_mark = match options.byte_order_mark {
+		ByteOrderMark::Keep => "keep",
+		ByteOrderMark::Remove => "remove",
+	};
+
+	format!(
+		r#"{{
+	// The options of the formatter, for all the languages
+	"formatter": {{
+		// "tab" or "space"
+		"indentStyle": "{indent_style}",
+		// The number of spaces of an indentation level, when using spaces
+		"indentSize": {indent_size},
+		// The maximum width of a line
+		"lineWidth": {line_width},
+		// "double" or "single"
+		"quoteStyle": "{quote_style}",
+		// "lf", "crlf" or "cr"
+		"lineEnding": "{line_ending}",
+		// "keep" or "remove", what happens to the byte order mark at the start of the files
+		"byteOrderMark": "{byte_order_mark}"
+	}},
+	// The options of the linter
+	"linter": {{
+		"enabled": true,
+		// The level of the rules, "off", "warn" or "error", for example:
+		// "rules": {{ "noDoubleEquals": "warn" }}
+		"rules": {{}}
+	}},
+	// The options of the formatter can be overridden for a language, for example:
+	// "json": {{ "formatter": {{ "indentStyle": "space" }} }},
+	// Glob patterns, relative to this file, of the files to process
+	"files": {{
+		// When empty, all the files are included
+		"include": [],
+		"ignore": []
+	}}
+}}
+"#,
+		indent_style = indent_style,
+		indent_size = indent_size,
+		line_width = options.line_width,
+		quote_style = quote_style,
+		line_ending = line_ending,
+		byte_order_mark = byte_order_mark,
+	)
+}
+
+#[cfg(test)]
+mod test {
+	use super::{
+		configuration_template, detect_formatter_configuration, read_prettier_configuration,
+	};
+	use crate::configuration::{parse_configuration, FormatterConfiguration};
+	use rome_formatter::{IndentStyle, LineEnding, QuoteStyle};
+	use rome_path::MemoryFileSystem;
+	use std::path::Path;
+
+	#[test]
+	fn template_is_valid_configuration() {
+		let formatter = FormatterConfiguration {
+			indent_style: Some(IndentStyle::Space(2)),
+			indent_size: Some(4),
+			quote_style: Some(QuoteStyle::Single),
+			..FormatterConfiguration::default()
+		};
+		let configuration = parse_configuration(&configuration_template(&formatter), 0).unwrap();
+
+		assert_eq!(
+			configuration.formatter.indent_style,
+			Some(IndentStyle::Space(2))
+		);
+		assert_eq!(configuration.formatter.indent_size, Some(4));
+		assert_eq!(configuration.formatter.line_width, Some(80));
+		assert_eq!(
+			configuration.formatter.quote_style,
+			Some(QuoteStyle::Single)
+		);
+		assert_eq!(
+			configuration.formatter.line_ending,
+			Some(LineEnding::LineFeed)
+		);
+	}
+
+	#[test]
+	fn reads_prettier_configuration() {
+		let formatter = read_prettier_configuration(
+			r#"{ "useTabs": true, "printWidth": 120, "singleQuote": true }"#,
+		)
+		.unwrap();
+
+		assert_eq!(formatter.indent_style, Some(IndentStyle::Tab));
+		assert_eq!(formatter.line_width, Some(120));
+		assert_eq!(formatter.quote_style, Some(QuoteStyle::Single));
+		assert!(read_prettier_configuration("semi: false").is_none());
+
+		let formatter = read_prettier_configuration(r#"{ "tabWidth": 4 }"#).unwrap();
+		assert_eq!(formatter.indent_style, None);
+		assert_eq!(formatter.indent_size, Some(4));
+
+		let formatter = read_prettier_configuration(r#"{ "useTabs": false }"#).unwrap();
+		assert_eq!(formatter.indent_style, Some(IndentStyle::Space(2)));
+		let formatter = read_prettier_configuration(r#"{ "semi": false }"#).unwrap();
+		assert_eq!(formatter.indent_style, None);
+	}
+
+	#[test]
+	fn detects_formatter_configuration() {
+		// the width alone means spaces, as it's the default of Prettier
+		let fs = MemoryFileSystem::default().with_file(".prettierrc", r#"{ "tabWidth": 4 }"#);
+		let (formatter, sources) = detect_formatter_configuration(&fs, Path::new("."));
+		assert_eq!(sources, vec![".prettierrc"]);
+		assert_eq!(
+			formatter.to_format_options().indent_style,
+			IndentStyle::Space(4)
+		);
+
+		// unless the `.editorconfig` file sets the style
+		let fs = MemoryFileSystem::default()
+			.with_file(".editorconfig", "[*]\nindent_style = tab\n")
+			.with_file(".prettierrc", r#"{ "tabWidth": 4 }"#);
+		let (formatter, sources) = detect_formatter_configuration(&fs, Path::new("."));
+		assert_eq!(sources, vec![".editorconfig", ".prettierrc"]);
+		assert_eq!(formatter.indent_style, Some(IndentStyle::Tab));
+		assert_eq!(formatter.indent_size, Some(4));
+		assert_eq!(formatter.to_format_options().indent_style, IndentStyle::Tab);
+
+		let fs = MemoryFileSystem::default()
+			.with_file(".editorconfig", "[*]\nindent_style = tab\n")
+			.with_file(".prettierrc", r#"{ "useTabs": false, "tabWidth": 4 }"#);
+		let (formatter, _) = detect_formatter_configuration(&fs, Path::new("."));
+		assert_eq!(
+			formatter.to_format_options().indent_style,
+			IndentStyle::Space(4)
+		);
+	}
+}
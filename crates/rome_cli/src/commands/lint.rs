@@ -0,0 +1,454 @@
+use crate::cache::{Cache, LINT_CACHE_FILE_NAME};
+use crate::commands::{
+	cache_directory, load_project_configuration, EXIT_CHECK_FAILED, EXIT_FAILURE, EXIT_INTERRUPTED,
+};
+use crate::reporter::{create_reporter, Mode, Reporter, Status, Summary};
+use crate::runner::{is_interrupted, process_files, RunStatus};
+use crate::traversal::{collect_files, TraversalOptions};
+use clap::ArgMatches;
+use rome_analyzer::{apply_fixes, Analyzer, FixMode, RuleRegistry};
+use rome_core::{create_app, App, Workspace};
+use rome_path::{strip_byte_order_mark, FileSystem, OsFileSystem, RomePath, BYTE_ORDER_MARK};
+use rslint_errors::file::FileId;
+use rslint_errors::{Diagnostic, Severity};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// What happened to a single file
+enum FileOutcome {
+	/// The file has been linted, the diagnostics come from the parser and from the rules.
+	///
+	/// When fixes have been applied, the source and the diagnostics are the ones of the fixed code
+	Linted {
+		source: String,
+		diagnostics: Vec<Diagnostic>,
+		fixes: usize,
+	},
+	/// The cache knows that the file, with the same content and rules, has no diagnostics
+	Cached,
+	/// The file can't be linted
+	Skipped,
+	/// The run has been interrupted before writing the fixes of the file
+	Interrupted,
+	/// The file couldn't be read, the diagnostic explains why
+	Failed(Diagnostic),
+}
+
+/// Handler for the `lint` command
+pub(crate) fn lint(matches: &ArgMatches) {
+	let respect_ignore = !matches.is_present("no_ignore");
+	let is_verbose = matches.is_present("verbose");
+	// the value has already been validated
+	let threads = matches
+		.value_of("threads")
+		.map(|threads| threads.parse::<usize>().unwrap());
+	// the value has already been validated
+	let reporter_kind = matches
+		.value_of("reporter")
+		.map(|kind| kind.parse().unwrap())
+		.unwrap_or_default();
+	let fix_mode = if matches.is_present("apply_unsafe") {
+		Some(FixMode::Unsafe)
+	} else if matches.is_present("apply") {
+		Some(FixMode::Safe)
+	} else {
+		None
+	};
+
+	let fs = OsFileSystem;
+	let loaded_configuration = load_project_configuration();
+	let project_directory = loaded_configuration
+		.as_ref()
+		.map(|loaded| loaded.root().to_path_buf());
+	let files_filter = loaded_configuration
+		.as_ref()
+		.map(|loaded| loaded.files_filter(&fs, respect_ignore));
+	let linter = loaded_configuration
+		.map(|loaded| loaded.configuration.linter)
+		.unwrap_or_default();
+	if !linter.enabled {
+		eprintln!("The linter is disabled in the configuration");
+		return;
+	}
+
+	let options = TraversalOptions {
+		respect_ignore_files: respect_ignore,
+		collect_ignored: is_verbose,
+		filter: files_filter.as_ref(),
+	};
+	let inputs = matches.values_of("input").into_iter().flatten();
+	let collected = match collect_files(&fs, inputs, &options) {
+		Ok(collected) => collected,
+		Err(message) => {
+			eprintln!("{}", message);
+			std::process::exit(EXIT_FAILURE);
+		}
+	};
+
+	let mut reporter = create_reporter(reporter_kind, Mode::Lint, is_verbose, false);
+	for path in &collected.ignored {
+		reporter.report_ignored(path);
+	}
+
+	let cache = if matches.is_present("no_cache") {
+		None
+	} else {
+		cache_directory(matches, project_directory.as_deref())
+			.map(|directory| Cache::load(&directory, LINT_CACHE_FILE_NAME))
+	};
+
+	let app = create_app();
+	let registry = RuleRegistry::new();
+	let analyzer = registry.analyzer(&linter.rules);
+	let runner = LintRunner {
+		fs: &fs,
+		app: &app,
+		analyzer: &analyzer,
+		threads,
+		fix_mode,
+		cache: cache.as_ref(),
+	};
+	let (summary, status) = runner.run(&collected.files, reporter.as_mut());
+
+	if status == RunStatus::Interrupted {
+		eprintln!("The process has been interrupted, some files haven't been linted");
+		std::process::exit(EXIT_INTERRUPTED);
+	}
+	if summary.failed > 0 {
+		std::process::exit(EXIT_FAILURE);
+	}
+	if summary.errors > 0 {
+		std::process::exit(EXIT_CHECK_FAILED);
+	}
+}
+
+/// Lints lists of files with the same rules
+struct LintRunner<'a> {
+	fs: &'a dyn FileSystem,
+	app: &'a App,
+	analyzer: &'a Analyzer<'a>,
+	threads: Option<usize>,
+	/// When set, the fixes are applied and the files are written
+	fix_mode: Option<FixMode>,
+	cache: Option<&'a Cache>,
+}
+
+impl LintRunner<'_> {
+	/// Lints the files, reporting their diagnostics and the summary to `reporter`
+	fn run(&self, files: &[PathBuf], reporter: &mut dyn Reporter) -> (Summary, RunStatus) {
+		let mut summary = Summary::default();
+		// the ids are assigned before processing the files, so the diagnostics point to their file
+		let mut workspace = Workspace::new();
+		// the cache is written by the runner itself
+		let files: Vec<_> = match self.cache {
+			Some(cache) => files
+				.iter()
+				.filter(|path| !cache.is_cache_file(self.fs, path))
+				.cloned()
+				.collect(),
+			None => files.to_vec(),
+		};
+		let file_ids: HashMap<&Path, FileId> = files
+			.iter()
+			.map(|path| (path.as_path(), workspace.insert(path, String::new())))
+			.collect();
+
+		let status = process_files(
+			&files,
+			self.threads,
+			|path| {
+				let start = Instant::now();
+				let outcome = self.lint_path(path, file_ids[path]);
+				(outcome, start.elapsed())
+			},
+			|path, (outcome, duration)| match outcome {
+				FileOutcome::Linted {
+					source,
+					diagnostics,
+					fixes,
+				} => {
+					summary.linted += 1;
+					summary.fixes += fixes;
+					workspace.insert(path, source);
+					for diagnostic in &diagnostics {
+						if is_error(diagnostic) {
+							summary.errors += 1;
+						} else if let Severity::Warning = diagnostic.severity {
+							summary.warnings += 1;
+						}
+						reporter.report_diagnostic(&workspace, diagnostic);
+					}
+					reporter.report_file(path, Status::Linted, duration);
+				}
+				FileOutcome::Cached => {
+					summary.linted += 1;
+					reporter.report_file(path, Status::Linted, duration);
+				}
+				FileOutcome::Skipped => {
+					summary.skipped += 1;
+					reporter.report_file(path, Status::Skipped, duration);
+				}
+				FileOutcome::Interrupted => {}
+				FileOutcome::Failed(diagnostic) => {
+					summary.failed += 1;
+					reporter.report_diagnostic(&workspace, &diagnostic);
+					reporter.report_file(path, Status::Failed(&diagnostic.title), duration);
+				}
+			},
+		);
+
+		reporter.report_summary(&summary);
+
+		if let Some(cache) = self.cache {
+			if let Err(error) = cache.save() {
+				eprintln!("Cannot write the cache: {}", error);
+			}
+		}
+
+		(summary, status)
+	}
+
+	/// Lints a file, and applies the fixes when the runner has a fix mode. The files that the cache
+	/// knows to have no diagnostics are skipped without parsing them
+	fn lint_path(&self, path: &Path, file_id: FileId) -> FileOutcome {
+		let mut rome_path = RomePath::new(path)
+			.with_file_system(self.fs)
+			.deduce_handler(self.app);
+		let handler = match rome_path.get_handler() {
+			Some(handler) if handler.capabilities().lint => handler,
+			_ => return FileOutcome::Skipped,
+		};
+
+		let content = match rome_path.read_to_string() {
+			Ok(content) => content,
+			Err(error) => return FileOutcome::Failed(error.to_diagnostic(file_id, path)),
+		};
+
+		let cached = self
+			.cache
+			.and_then(|cache| Some((cache, self.fs.canonicalize(path).ok()?)));
+		if let Some((cache, canonical_path)) = &cached {
+			let fingerprint = Cache::lint_fingerprint(&content, self.analyzer);
+			if cache.is_cached(canonical_path, fingerprint) {
+				return FileOutcome::Cached;
+			}
+		}
+		// only the files without diagnostics are recorded, the others are linted by every run so
+		// their diagnostics are reported
+		let record = |content: &str, diagnostics: &[Diagnostic]| match &cached {
+			Some((cache, canonical_path)) if diagnostics.is_empty() => {
+				let fingerprint = Cache::lint_fingerprint(content, self.analyzer);
+				cache.record(canonical_path.clone(), fingerprint);
+			}
+			_ => {}
+		};
+
+		// the byte order mark isn't part of the code
+		let (source, has_byte_order_mark) = strip_byte_order_mark(&content);
+		let parse = match handler.parse(source, file_id) {
+			Some(parse) => parse,
+			None => return FileOutcome::Skipped,
+		};
+
+		let has_syntax_errors = parse.diagnostics.iter().any(is_error);
+		let mut diagnostics = parse.diagnostics;
+		diagnostics.extend(self.analyzer.analyze(&parse.root, file_id));
+
+		// the fixes can't be checked on a code that is already invalid
+		let fix_mode = match self.fix_mode {
+			Some(fix_mode) if !has_syntax_errors => fix_mode,
+			_ => {
+				record(&content, &diagnostics);
+				return FileOutcome::Linted {
+					source: source.to_string(),
+					diagnostics,
+					fixes: 0,
+				};
+			}
+		};
+		let fixed = apply_fixes(source, diagnostics, fix_mode, |code| {
+			let parse = handler.parse(code, file_id)?;
+			if parse.diagnostics.iter().any(is_error) {
+				return None;
+			}
+			Some(self.analyzer.analyze(&parse.root, file_id))
+		});
+
+		if fixed.applied > 0 {
+			// the file is checked right before writing it, a file that has been started is always completed
+			if is_interrupted() {
+				return FileOutcome::Interrupted;
+			}
+			let content = if has_byte_order_mark {
+				format!("{}{}", BYTE_ORDER_MARK, fixed.code)
+			} else {
+				fixed.code.clone()
+			};
+			if let Err(error) = rome_path.save(&content) {
+				return FileOutcome::Failed(error.to_diagnostic(file_id, path));
+			}
+			// the entry is the one of the fixed content, that the file has from now on
+			record(&content, &fixed.diagnostics);
+		} else {
+			record(&content, &fixed.diagnostics);
+		}
+		FileOutcome::Linted {
+			source: fixed.code,
+			diagnostics: fixed.diagnostics,
+			fixes: fixed.applied,
+		}
+	}
+}
+
+fn is_error(diagnostic: &Diagnostic) -> bool {
+	matches!(diagnostic.severity, Severity::Bug | Severity::Error)
+}
+
+#[cfg(test)]
+mod test {
+	use super::{FileOutcome, LintRunner};
+	use crate::cache::{Cache, LINT_CACHE_FILE_NAME};
+	use crate::reporter::{JsonReporter, Mode, Summary};
+	use crate::runner::RunStatus;
+	use rome_analyzer::{FixMode, RuleLevel, RuleRegistry, RulesConfiguration};
+	use rome_core::create_app;
+	use rome_path::{FileSystem, MemoryFileSystem};
+	use std::path::{Path, PathBuf};
+
+	#[test]
+	fn lints_in_memory() {
+		let fs = MemoryFileSystem::default()
+			.with_file("src/index.js", "debugger;\nif (a == b) {}\n")
+			.with_file("src/clean.js", "let a = [1, 2];\n")
+			.with_file("README.md", "# Rome\n");
+		let files: Vec<_> = ["src/index.js", "src/clean.js", "README.md"]
+			.iter()
+			.map(PathBuf::from)
+			.collect();
+
+		let app = create_app();
+		let registry = RuleRegistry::new();
+		let mut rules = RulesConfiguration::default();
+		rules.set("noDoubleEquals", RuleLevel::Warn);
+		let analyzer = registry.analyzer(&rules);
+		let runner = LintRunner {
+			fs: &fs,
+			app: &app,
+			analyzer: &analyzer,
+			threads: Some(2),
+			fix_mode: None,
+			cache: None,
+		};
+
+		let mut output = Vec::new();
+		let (summary, status) = runner.run(&files, &mut JsonReporter::new(&mut output, Mode::Lint));
+		assert_eq!(status, RunStatus::Completed);
+		assert_eq!(
+			summary,
+			Summary {
+				linted: 2,
+				skipped: 1,
+				errors: 1,
+				warnings: 1,
+				..Summary::default()
+			}
+		);
+
+		let output = String::from_utf8(output).unwrap();
+		assert!(output.contains(r#""code":"lint/noDebugger""#));
+		assert!(output.contains(r#""code":"lint/noDoubleEquals""#));
+		assert!(output.contains(r#""location":{"start":{"line":2,"#));
+	}
+
+	#[test]
+	fn applies_fixes() {
+		let fs = MemoryFileSystem::default()
+			.with_file("src/index.js", "\u{feff}debugger;\nif (a == b) {}\n")
+			.with_file("src/broken.js", "debugger;\nlet = ;\n");
+		let files: Vec<_> = ["src/index.js", "src/broken.js"]
+			.iter()
+			.map(PathBuf::from)
+			.collect();
+
+		let app = create_app();
+		let registry = RuleRegistry::new();
+		let analyzer = registry.analyzer(&RulesConfiguration::default());
+		let runner = LintRunner {
+			fs: &fs,
+			app: &app,
+			analyzer: &analyzer,
+			threads: Some(2),
+			fix_mode: Some(FixMode::Safe),
+			cache: None,
+		};
+
+		let mut output = Vec::new();
+		let (summary, status) = runner.run(&files, &mut JsonReporter::new(&mut output, Mode::Lint));
+		assert_eq!(status, RunStatus::Completed);
+		// the unsafe fix of noDoubleEquals isn't applied, and the file with syntax errors isn't fixed
+		assert_eq!(summary.fixes, 1);
+		assert_eq!(
+			fs.read(Path::new("src/index.js")).unwrap(),
+			"\u{feff}\nif (a == b) {}\n".as_bytes()
+		);
+		assert_eq!(
+			fs.read(Path::new("src/broken.js")).unwrap(),
+			b"debugger;\nlet = ;\n"
+		);
+
+		let output = String::from_utf8(output).unwrap();
+		let codes: Vec<_> = output
+			.lines()
+			.map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+			.filter(|record| record["file"] == "src/index.js")
+			.map(|record| record["code"].as_str().unwrap().to_string())
+			.collect();
+		assert_eq!(codes, vec!["lint/noDoubleEquals"]);
+	}
+
+	#[test]
+	fn skips_cached_files() {
+		let fs = MemoryFileSystem::default()
+			.with_file("src/clean.js", "let a = [1, 2];\n")
+			.with_file("src/index.js", "debugger;\n");
+
+		let app = create_app();
+		let registry = RuleRegistry::new();
+		let analyzer = registry.analyzer(&RulesConfiguration::default());
+		// the cache is only written at the end of a run, the file doesn't exist
+		let cache = Cache::load(Path::new("/rome_lint_cache"), LINT_CACHE_FILE_NAME);
+		let runner = LintRunner {
+			fs: &fs,
+			app: &app,
+			analyzer: &analyzer,
+			threads: Some(1),
+			fix_mode: None,
+			cache: Some(&cache),
+		};
+		fn is_cached(runner: &LintRunner, path: &str) -> bool {
+			matches!(runner.lint_path(Path::new(path), 0), FileOutcome::Cached)
+		}
+
+		assert!(!is_cached(&runner, "src/clean.js"));
+		assert!(is_cached(&runner, "src/clean.js"));
+		// the files with diagnostics are linted by every run
+		assert!(!is_cached(&runner, "src/index.js"));
+		assert!(!is_cached(&runner, "src/index.js"));
+
+		fs.write(Path::new("src/clean.js"), b"let a = [1, 2, 3];\n")
+			.unwrap();
+		assert!(!is_cached(&runner, "src/clean.js"));
+		assert!(is_cached(&runner, "src/clean.js"));
+
+		// changing the level of a rule invalidates the entries
+		let mut rules = RulesConfiguration::default();
+		rules.set("noDebugger", RuleLevel::Warn);
+		let analyzer = registry.analyzer(&rules);
+		let runner = LintRunner {
+			analyzer: &analyzer,
+			..runner
+		};
+		assert!(!is_cached(&runner, "src/clean.js"));
+	}
+}
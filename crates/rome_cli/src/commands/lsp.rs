@@ -0,0 +1,20 @@
+use crate::commands::EXIT_FAILURE;
+use crate::lsp::run_server;
+use clap::ArgMatches;
+use std::io;
+
+/// Handler for the `lsp` command, the server runs until the editor asks it to exit
+pub(crate) fn lsp(_matches: &ArgMatches) {
+	let stdin = io::stdin();
+	let stdout = io::stdout();
+
+	match run_server(stdin.lock(), stdout.lock()) {
+		// the protocol requires a failure when the editor exits without shutting down the server
+		Ok(true) => {}
+		Ok(false) => std::process::exit(EXIT_FAILURE),
+		Err(error) => {
+			eprintln!("The language server has stopped: {}", error);
+			std::process::exit(EXIT_FAILURE);
+		}
+	}
+}
@@ -0,0 +1,62 @@
+use crate::cache::DEFAULT_CACHE_DIRECTORY;
+use crate::configuration::{load_configuration, LoadedConfiguration};
+use clap::ArgMatches;
+use std::env;
+use std::path::{Path, PathBuf};
+
+pub(crate) mod format;
+pub(crate) mod init;
+pub(crate) mod lint;
+pub(crate) mod lsp;
+pub(crate) mod parse;
+
+/// Exit code used when one or more files couldn't be processed
+pub(crate) const EXIT_FAILURE: i32 = 1;
+
+/// Exit code used by `format --check` when one or more files aren't formatted, and by `lint`
+/// when the rules report errors.
+///
+/// `2` isn't used because it's the code of clap when the arguments are invalid.
+pub(crate) const EXIT_CHECK_FAILED: i32 = 3;
+
+/// Exit code used when the process has been interrupted with Ctrl-C, following
+/// the convention of the shells (128 + SIGINT)
+pub(crate) const EXIT_INTERRUPTED: i32 = 130;
+
+/// Loads the configuration of the project that contains the working directory.
+///
+/// When the configuration is invalid, the diagnostics are printed and the process exits
+pub(crate) fn load_project_configuration() -> Option<LoadedConfiguration> {
+	let working_directory = match env::current_dir() {
+		Ok(directory) => directory,
+		Err(error) => {
+			eprintln!("Cannot read the working directory: {}", error);
+			std::process::exit(EXIT_FAILURE);
+		}
+	};
+
+	match load_configuration(&working_directory) {
+		Ok(configuration) => configuration,
+		Err(error) => {
+			error.emit();
+			std::process::exit(EXIT_FAILURE);
+		}
+	}
+}
+
+/// The directory of the cache, passed as argument or inside `node_modules` when the project has it
+pub(crate) fn cache_directory(
+	matches: &ArgMatches,
+	project_directory: Option<&Path>,
+) -> Option<PathBuf> {
+	if let Some(directory) = matches.value_of("cache_dir") {
+		return Some(PathBuf::from(directory));
+	}
+
+	let project_directory = project_directory.unwrap_or_else(|| Path::new("."));
+	if project_directory.join("node_modules").is_dir() {
+		Some(project_directory.join(DEFAULT_CACHE_DIRECTORY))
+	} else {
+		None
+	}
+}
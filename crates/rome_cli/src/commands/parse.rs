@@ -0,0 +1,98 @@
+use crate::commands::EXIT_FAILURE;
+use clap::ArgMatches;
+use rome_core::file_handlers::Parse;
+use rome_core::{create_app, Workspace};
+use rome_path::{strip_byte_order_mark, RomePath};
+use rslint_errors::termcolor::{ColorChoice, StandardStream};
+use rslint_errors::Emitter;
+use rslint_parser::{parse_with_syntax, tokenize, Syntax};
+use std::io::Write;
+use std::path::Path;
+
+/// Handler for the `parse` command, a tool to debug the parser
+pub(crate) fn parse(matches: &ArgMatches) {
+	// the input is required
+	let input = matches.value_of("input").unwrap();
+	let path = Path::new(input);
+	let content = match RomePath::new(path).read_to_string() {
+		Ok(content) => content,
+		Err(error) => {
+			eprintln!("Cannot parse \"{}\": {}", input, error);
+			std::process::exit(EXIT_FAILURE);
+		}
+	};
+	// the byte order mark isn't part of the code
+	let (source, _) = strip_byte_order_mark(&content);
+	let mut workspace = Workspace::new();
+	let file_id = workspace.insert(path, source);
+
+	let color = if atty::is(atty::Stream::Stdout) {
+		ColorChoice::Auto
+	} else {
+		ColorChoice::Never
+	};
+	let mut stdout = StandardStream::stdout(color);
+
+	let print_tokens = matches.is_present("tokens");
+	let print_cst = matches.is_present("cst");
+
+	if print_tokens {
+		let (tokens, _) = tokenize(source, file_id);
+		let mut offset = 0;
+		for token in tokens {
+			let end = offset + token.len;
+			writeln!(
+				stdout,
+				"{:?}@{}..{} {:?}",
+				token.kind,
+				offset,
+				end,
+				&source[offset..end]
+			)
+			.expect("cannot print the tokens");
+			offset = end;
+		}
+	}
+
+	let parse: Parse = match matches.value_of("source_type") {
+		Some(source_type) => {
+			parse_with_syntax(source, file_id, syntax_from_source_type(source_type)).into()
+		}
+		// the files that the handlers can't parse are parsed as scripts
+		None => workspace
+			.parse(&create_app(), file_id)
+			.unwrap_or_else(|| parse_with_syntax(source, file_id, Syntax::default()).into()),
+	};
+
+	if print_cst {
+		write!(stdout, "{:#?}", parse.root).expect("cannot print the syntax tree");
+	}
+
+	if !print_tokens && !print_cst {
+		let mut emitter = Emitter::new(&workspace);
+		for diagnostic in &parse.diagnostics {
+			emitter
+				.emit_with_writer(diagnostic, &mut stdout)
+				.expect("cannot print the diagnostic");
+		}
+		writeln!(
+			stdout,
+			"Found {} diagnostics in \"{}\"",
+			parse.diagnostics.len(),
+			input
+		)
+		.expect("cannot print the diagnostics");
+
+		if !parse.diagnostics.is_empty() {
+			std::process::exit(EXIT_FAILURE);
+		}
+	}
+}
+
+fn syntax_from_source_type(source_type: &str) -> Syntax {
+	match source_type {
+		"module" => Syntax::default().module(),
+		"typescript" => Syntax::default().typescript(),
+		_ => Syntax::default().script(),
+	}
+}
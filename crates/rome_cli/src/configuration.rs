@@ -0,0 +1,804 @@
+//! Loads the configuration of the project from the `rome.json` file.
+//!
+//! The file is searched in the working directory and then in its ancestors: the directory that
+//! contains the file is the root of the project.
+//!
+//! The file is parsed as a JavaScript expression, so the diagnostics of an invalid configuration
+//! point to the exact location of the invalid key or value. A configuration looks like this:
+//!
+//! ```json
+//! {
+//!   "formatter": {
+//!     "indentStyle": "space",
+//!     "indentSize": 4,
+//!     "lineWidth": 100,
+//!     "quoteStyle": "single",
+//!     "lineEnding": "lf",
+//!     "byteOrderMark": "keep"
+//!   },
+//!   "linter": {
+//!     "enabled": true,
+//!     "rules": { "noDebugger": "off", "noDoubleEquals": "warn" }
+//!   },
+//!   "json": {
+//!     "formatter": { "indentStyle": "tab" }
+//!   },
+//!   "files": {
+//!     "include": ["src/**"],
+//!     "ignore": ["**/*.min.js"]
+//!   }
+//! }
+//! ```
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use rome_analyzer::{RuleRegistry, RulesConfiguration};
+use rome_core::file_handlers::Language;
+use rome_formatter::{ByteOrderMark, FormatOptions, IndentStyle, LineEnding, QuoteStyle};
+use rome_path::FileSystem;
+use rslint_errors::file::{FileId, SimpleFile};
+use rslint_errors::{Diagnostic, Emitter};
+use rslint_parser::ast::{Expr, ExprOrSpread, LiteralKind, ObjectProp, PropName};
+use rslint_parser::{parse_expr, AstNode, SyntaxNodeExt, TextRange};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Name of the configuration file
+pub(crate) const CONFIGURATION_FILE_NAME: &str = "rome.json";
+
+/// Options of the formatter.
+///
+/// The options that are not set fall back to the options of a less specific configuration,
+/// and eventually to the defaults of the formatter
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub(crate) struct FormatterConfiguration {
+	pub(crate) indent_style: Option<IndentStyle>,
+	/// Only used when the indent style is [IndentStyle::Space]
+	pub(crate) indent_size: Option<u8>,
+	/// The width of a tab character. It can't be configured in `rome.json`
+	pub(crate) tab_width: Option<u8>,
+	pub(crate) line_width: Option<u16>,
+	pub(crate) quote_style: Option<QuoteStyle>,
+	pub(crate) line_ending: Option<LineEnding>,
+	pub(crate) byte_order_mark: Option<ByteOrderMark>,
+}
+
+impl FormatterConfiguration {
+	/// Overrides the options of this configuration with the options set in `other`
+	pub(crate) fn merge(&mut self, other: &FormatterConfiguration) {
+		if other.indent_style.is_some() {
+			self.indent_style = other.indent_style.clone();
+		}
+		if other.indent_size.is_some() {
+			self.indent_size = other.indent_size;
+		}
+		if other.tab_width.is_some() {
+			self.tab_width = other.tab_width;
+		}
+		if other.line_width.is_some() {
+			self.line_width = other.line_width;
+		}
+		if other.quote_style.is_some() {
+			self.quote_style = other.quote_style;
+		}
+		if other.line_ending.is_some() {
+			self.line_ending = other.line_ending.clone();
+		}
+		if other.byte_order_mark.is_some() {
+			self.byte_order_mark = other.byte_order_mark;
+		}
+	}
+
+	/// Creates the options of the formatter, using the defaults for the options that are not set
+	pub(crate) fn to_format_options(&self) -> FormatOptions {
+		let defaults = FormatOptions::default();
+		let indent_style = match &self.indent_style {
+			Some(IndentStyle::Space(size)) => IndentStyle::Space(self.indent_size.unwrap_or(*size)),
+			Some(IndentStyle::Tab) => IndentStyle::Tab,
+			None => defaults.indent_style,
+		};
+
+		FormatOptions {
+			indent_style,
+			tab_width: self.tab_width.unwrap_or(defaults.tab_width),
+			line_width: self.line_width.unwrap_or(defaults.line_width),
+			quote_style: self.quote_style.unwrap_or(defaults.quote_style),
+			line_ending: self.line_ending.clone().unwrap_or(defaults.line_ending),
+			byte_order_mark: self.byte_order_mark.unwrap_or(defaults.byte_order_mark),
+		}
+	}
+}
+
+/// Options of the linter
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct LinterConfiguration {
+	/// When `false`, the files aren't linted
+	pub(crate) enabled: bool,
+	pub(crate) rules: RulesConfiguration,
+}
+
+impl Default for LinterConfiguration {
+	fn default() -> Self {
+		Self {
+			enabled: true,
+			rules: RulesConfiguration::default(),
+		}
+	}
+}
+
+/// Options that only apply to the files of a language
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub(crate) struct LanguageConfiguration {
+	pub(crate) formatter: FormatterConfiguration,
+}
+
+/// Glob patterns, relative to the root of the project, that select the files to process
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub(crate) struct FilesConfiguration {
+	/// When empty, all the files are included
+	pub(crate) include: Vec<String>,
+	pub(crate) ignore: Vec<String>,
+}
+
+/// The content of the `rome.json` file
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub(crate) struct Configuration {
+	pub(crate) formatter: FormatterConfiguration,
+	pub(crate) linter: LinterConfiguration,
+	pub(crate) javascript: LanguageConfiguration,
+	pub(crate) typescript: LanguageConfiguration,
+	pub(crate) json: LanguageConfiguration,
+	pub(crate) files: FilesConfiguration,
+}
+
+impl Configuration {
+	/// Returns the options of the formatter for a file of the given language, where the
+	/// options of the language take precedence over the options of the `formatter` section
+	pub(crate) fn formatter_for(&self, language: Option<Language>) -> FormatterConfiguration {
+		let mut formatter = self.formatter.clone();
+		match language {
+			Some(Language::Js) => formatter.merge(&self.javascript.formatter),
+			Some(Language::Ts) => formatter.merge(&self.typescript.formatter),
+			Some(Language::Json) => formatter.merge(&self.json.formatter),
+			Some(Language::Unknown) | None => {}
+		}
+		formatter
+	}
+}
+
+/// A configuration read from the disk
+#[derive(Debug)]
+pub(crate) struct LoadedConfiguration {
+	/// The path of the `rome.json` file
+	pub(crate) path: PathBuf,
+	pub(crate) configuration: Configuration,
+}
+
+impl LoadedConfiguration {
+	/// The directory that contains the configuration file
+	pub(crate) fn root(&self) -> &Path {
+		self.path.parent().unwrap_or_else(|| Path::new("."))
+	}
+
+	/// Creates the filter of the files configured in the `files` section. The `ignore`
+	/// patterns are only used when `respect_ignore` is `true`
+	pub(crate) fn files_filter(&self, fs: &dyn FileSystem, respect_ignore: bool) -> FilesFilter {
+		let files = &self.configuration.files;
+		FilesFilter {
+			root: fs.canonicalize(self.root()).ok(),
+			include: if files.include.is_empty() {
+				None
+			} else {
+				Some(build_glob_set(&files.include))
+			},
+			ignore: if respect_ignore {
+				build_glob_set(&files.ignore)
+			} else {
+				GlobSet::empty()
+			},
+		}
+	}
+}
+
+/// Builds a set of patterns that have already been validated
+fn build_glob_set(patterns: &[String]) -> GlobSet {
+	let mut builder = GlobSetBuilder::new();
+	for pattern in patterns {
+		builder.add(Glob::new(pattern).expect("the pattern has already been validated"));
+	}
+	builder
+		.build()
+		.expect("the patterns have already been validated")
+}
+
+/// Selects the files that should be processed, following the `files` section of the configuration
+pub(crate) struct FilesFilter {
+	root: Option<PathBuf>,
+	include: Option<GlobSet>,
+	ignore: GlobSet,
+}
+
+impl FilesFilter {
+	/// Returns `true` if the file should be processed.
+	///
+	/// The patterns are matched against the path relative to the root of the project, the files
+	/// outside of the project are always processed
+	pub(crate) fn is_included(&self, fs: &dyn FileSystem, path: &Path) -> bool {
+		let relative = match (&self.root, fs.canonicalize(path)) {
+			(Some(root), Ok(path)) => match path.strip_prefix(root) {
+				Ok(relative) => relative.to_path_buf(),
+				Err(_) => return true,
+			},
+			_ => return true,
+		};
+
+		let included = self
+			.include
+			.as_ref()
+			.map_or(true, |include| include.is_match(&relative));
+		included && !self.ignore.is_match(&relative)
+	}
+}
+
+/// The reasons why the configuration can't be loaded
+#[derive(Debug)]
+pub(crate) enum ConfigurationError {
+	/// The configuration file can't be read
+	Io { path: PathBuf, error: io::Error },
+	/// The content of the configuration file is invalid
+	Invalid {
+		path: PathBuf,
+		content: String,
+		diagnostics: Vec<Diagnostic>,
+	},
+}
+
+impl ConfigurationError {
+	/// Prints the error on stderr
+	pub(crate) fn emit(&self) {
+		match self {
+			ConfigurationError::Io { path, error } => {
+				eprintln!(
+					"Cannot read the configuration file \"{}\": {}",
+					path.display(),
+					error
+				);
+			}
+			ConfigurationError::Invalid {
+				path,
+				content,
+				diagnostics,
+			} => {
+				let file = SimpleFile::new(path.display().to_string(), content.clone());
+				let mut emitter = Emitter::new(&file);
+				for diagnostic in diagnostics {
+					emitter
+						.emit_stderr(diagnostic, atty::is(atty::Stream::Stderr))
+						.expect("cannot print the diagnostic");
+				}
+			}
+		}
+	}
+}
+
+/// Searches the configuration file in `directory` and in its ancestors
+pub(crate) fn find_configuration_file(directory: &Path) -> Option<PathBuf> {
+	directory
+		.ancestors()
+		.map(|directory| directory.join(CONFIGURATION_FILE_NAME))
+		.find(|path| path.is_file())
+}
+
+/// Loads the configuration of the project that contains `directory`.
+///
+/// Returns [None] when the project doesn't have a configuration file
+pub(crate) fn load_configuration(
+	directory: &Path,
+) -> Result<Option<LoadedConfiguration>, ConfigurationError> {
+	let path = match find_configuration_file(directory) {
+		Some(path) => path,
+		None => return Ok(None),
+	};
+
+	let content = match fs::read_to_string(&path) {
+		Ok(content) => content,
+		Err(error) => return Err(ConfigurationError::Io { path, error }),
+	};
+
+	match parse_configuration(&content, 0) {
+		Ok(configuration) => Ok(Some(LoadedConfiguration {
+			path,
+			configuration,
+		})),
+		Err(diagnostics) => Err(ConfigurationError::Invalid {
+			path,
+			content,
+			diagnostics,
+		}),
+	}
+}
+
+/// Parses and validates the content of a configuration file
+pub(crate) fn parse_configuration(
+	content: &str,
+	file_id: FileId,
+) -> Result<Configuration, Vec<Diagnostic>> {
+	let parse = parse_expr(content, file_id);
+	if !parse.errors().is_empty() {
+		return Err(parse.errors().to_vec());
+	}
+
+	let mut deserializer = Deserializer {
+		file_id,
+		diagnostics: Vec::new(),
+	};
+	let mut configuration = Configuration::default();
+	match parse.try_tree() {
+		Some(root) => deserializer.configuration(&root, &mut configuration),
+		None => deserializer.error(
+			parse.syntax().trimmed_range(),
+			"The configuration must be an object",
+			"expected an object",
+		),
+	}
+
+	if deserializer.diagnostics.is_empty() {
+		Ok(configuration)
+	} else {
+		Err(deserializer.diagnostics)
+	}
+}
+
+/// Walks the syntax tree of the configuration, collecting a diagnostic for each invalid key or value
+struct Deserializer {
+	file_id: FileId,
+	diagnostics: Vec<Diagnostic>,
+}
+
+/// A member of an object: its key, the range of the key and its value
+type Member = (String, TextRange, Expr);
+
+impl Deserializer {
+	fn error(&mut self, range: TextRange, title: impl Into<String>, label: impl Into<String>) {
+		self.diagnostics
+			.push(Diagnostic::error(self.file_id, "config", title).primary(range, label));
+	}
+
+	fn unknown_key(&mut self, key: &str, range: TextRange, known_keys: &[&str]) {
+		let diagnostic =
+			Diagnostic::error(self.file_id, "config", format!("Unknown key \"{}\"", key))
+				.primary(range, "this key isn't supported")
+				.footer_help(format!("the supported keys are: {}", known_keys.join(", ")));
+		self.diagnostics.push(diagnostic);
+	}
+
+	fn configuration(&mut self, root: &Expr, configuration: &mut Configuration) {
+		const KEYS: &[&str] = &[
+			"formatter",
+			"linter",
+			"javascript",
+			"typescript",
+			"json",
+			"files",
+		];
+
+		for (key, key_range, value) in self.object(root, "the configuration") {
+			match key.as_str() {
+				"formatter" => self.formatter(&value, &mut configuration.formatter),
+				"linter" => self.linter(&value, &mut configuration.linter),
+				"javascript" => self.language(&value, &key, &mut configuration.javascript),
+				"typescript" => self.language(&value, &key, &mut configuration.typescript),
+				"json" => self.language(&value, &key, &mut configuration.json),
+				"files" => self.files(&value, &mut configuration.files),
+				_ => self.unknown_key(&key, key_range, KEYS),
+			}
+		}
+	}
+
+	fn language(&mut self, value: &Expr, name: &str, language: &mut LanguageConfiguration) {
+		const KEYS: &[&str] = &["formatter"];
+
+		for (key, key_range, value) in self.object(value, name) {
+			match key.as_str() {
+				"formatter" => self.formatter(&value, &mut language.formatter),
+				_ => self.unknown_key(&key, key_range, KEYS),
+			}
+		}
+	}
+
+	fn formatter(&mut self, value: &Expr, formatter: &mut FormatterConfiguration) {
+		const KEYS: &[&str] = &[
+			"indentStyle",
+			"indentSize",
+			"lineWidth",
+			"quoteStyle",
+			"lineEnding",
+			"byteOrderMark",
+		];
+
+		for (key, key_range, value) in self.object(value, "formatter") {
+			match key.as_str() {
+				"indentStyle" => {
+					formatter.indent_style = self.variant(&value, &key, &["tab", "space"])
+				}
+				"indentSize" => {
+					formatter.indent_size = self
+						.integer(&value, &key, 1, u8::MAX as u64)
+						.map(|size| size as u8)
+				}
+				"lineWidth" => {
+					formatter.line_width = self
+						.integer(&value, &key, 1, u16::MAX as u64)
+						.map(|width| width as u16)
+				}
+				"quoteStyle" => {
+					formatter.quote_style = self.variant(&value, &key, &["double", "single"])
+				}
+				"lineEnding" => {
+					formatter.line_ending = self.variant(&value, &key, &["lf", "crlf", "cr"])
+				}
+				"byteOrderMark" => {
+					formatter.byte_order_mark = self.variant(&value, &key, &["keep", "remove"])
+				}
+				_ => self.unknown_key(&key, key_range, KEYS),
+			}
+		}
+	}
+
+	fn linter(&mut self, value: &Expr, linter: &mut LinterConfiguration) {
+		const KEYS: &[&str] = &["enabled", "rules"];
+
+		for (key, key_range, value) in self.object(value, "linter") {
+			match key.as_str() {
+				"enabled" => {
+					if let Some(enabled) = self.boolean(&value, &key) {
+						linter.enabled = enabled;
+					}
+				}
+				"rules" => self.rules(&value, &mut linter.rules),
+				_ => self.unknown_key(&key, key_range, KEYS),
+			}
+		}
+	}
+
+	/// Reads the levels of the rules, the keys are the names of the rules
+	fn rules(&mut self, value: &Expr, rules: &mut RulesConfiguration) {
+		let registry = RuleRegistry::new();
+
+		for (key, key_range, value) in self.object(value, "rules") {
+			if registry.get(&key).is_none() {
+				let names: Vec<_> = registry.rules().map(|rule| rule.name()).collect();
+				let diagnostic =
+					Diagnostic::error(self.file_id, "config", format!("Unknown rule \"{}\"", key))
+						.primary(key_range, "this rule doesn't exist")
+						.footer_help(format!("the rules are: {}", names.join(", ")));
+				self.diagnostics.push(diagnostic);
+				continue;
+			}
+			if let Some(level) = self.variant(&value, &key, &["off", "warn", "error"]) {
+				rules.set(key, level);
+			}
+		}
+	}
+
+	fn files(&mut self, value: &Expr, files: &mut FilesConfiguration) {
+		const KEYS: &[&str] = &["include", "ignore"];
+
+		for (key, key_range, value) in self.object(value, "files") {
+			match key.as_str() {
+				"include" => files.include = self.glob_patterns(&value, &key),
+				"ignore" => files.ignore = self.glob_patterns(&value, &key),
+				_ => self.unknown_key(&key, key_range, KEYS),
+			}
+		}
+	}
+
+	/// Returns the members of an object, or no members if the value isn't an object
+	fn object(&mut self, value: &Expr, name: &str) -> Vec<Member> {
+		let object = match value {
+			Expr::ObjectExpr(object) => object,
+			_ => {
+				self.invalid_type(value, name, "an object");
+				return Vec::new();
+			}
+		};
+
+		let mut members = Vec::new();
+		for prop in object.props().iter() {
+			let prop = match prop {
+				ObjectProp::LiteralProp(prop) => prop,
+				prop => {
+					self.error(
+						prop.syntax().trimmed_range(),
+						format!("The members of \"{}\" must be key-value pairs", name),
+						"expected a key-value pair",
+					);
+					continue;
+				}
+			};
+
+			let key = match prop.key() {
+				Ok(key) => key,
+				Err(_) => continue,
+			};
+			let key_range = key.syntax().trimmed_range();
+			let key_text = match &key {
+				PropName::Literal(literal) => {
+					literal.inner_string_text().map(|text| text.to_string())
+				}
+				PropName::Ident(_) | PropName::Name(_) => {
+					Some(key.syntax().trimmed_text().to_string())
+				}
+				PropName::ComputedPropertyName(_) | PropName::JsUnknownBinding(_) => None,
+			};
+
+			match (key_text, prop.value()) {
+				(Some(key), Ok(value)) => members.push((key, key_range, value)),
+				(None, _) => self.error(key_range, "The keys must be strings", "expected a string"),
+				(_, Err(_)) => {}
+			}
+		}
+		members
+	}
+
+	/// Reads a string converted with its [FromStr] implementation, `variants` are the accepted strings
+	fn variant<T: FromStr>(&mut self, value: &Expr, name: &str, variants: &[&str]) -> Option<T> {
+		let (text, range) = self.string(value, name)?;
+		match T::from_str(&text) {
+			Ok(variant) => Some(variant),
+			Err(_) => {
+				let variants: Vec<String> = variants
+					.iter()
+					.map(|variant| format!("\"{}\"", variant))
+					.collect();
+				self.error(
+					range,
+					format!("Invalid value for \"{}\"", name),
+					format!("expected one of {}", variants.join(", ")),
+				);
+				None
+			}
+		}
+	}
+
+	fn boolean(&mut self, value: &Expr, name: &str) -> Option<bool> {
+		match value {
+			Expr::Literal(literal) => match literal.kind() {
+				LiteralKind::Bool(value) => Some(value),
+				_ => {
+					self.invalid_type(value, name, "a boolean");
+					None
+				}
+			},
+			_ => {
+				self.invalid_type(value, name, "a boolean");
+				None
+			}
+		}
+	}
+
+	fn string(&mut self, value: &Expr, name: &str) -> Option<(String, TextRange)> {
+		match value {
+			Expr::Literal(literal) if literal.is_string() => literal
+				.inner_string_text()
+				.map(|text| (text.to_string(), literal.syntax().trimmed_range())),
+			_ => {
+				self.invalid_type(value, name, "a string");
+				None
+			}
+		}
+	}
+
+	/// Reads an integer between `min` and `max`, both included
+	fn integer(&mut self, value: &Expr, name: &str, min: u64, max: u64) -> Option<u64> {
+		let number = match value {
+			Expr::Literal(literal) => match literal.kind() {
+				LiteralKind::Number(number) => number,
+				_ => {
+					self.invalid_type(value, name, "a number");
+					return None;
+				}
+			},
+			_ => {
+				self.invalid_type(value, name, "a number");
+				return None;
+			}
+		};
+
+		if number.fract() == 0.0 && number >= min as f64 && number <= max as f64 {
+			Some(number as u64)
+		} else {
+			self.error(
+				value.syntax().trimmed_range(),
+				format!("Invalid value for \"{}\"", name),
+				format!("expected an integer between {} and {}", min, max),
+			);
+			None
+		}
+	}
+
+	/// Reads an array of glob patterns, reporting the patterns that are invalid
+	fn glob_patterns(&mut self, value: &Expr, name: &str) -> Vec<String> {
+		let array = match value {
+			Expr::ArrayExpr(array) => array,
+			_ => {
+				self.invalid_type(value, name, "an array");
+				return Vec::new();
+			}
+		};
+
+		let mut patterns = Vec::new();
+		for element in array.elements().iter() {
+			let element = match element {
+				ExprOrSpread::Expr(element) => element,
+				element => {
+					self.error(
+						element.syntax().trimmed_range(),
+						format!("Invalid type for \"{}\"", name),
+						"expected a string",
+					);
+					continue;
+				}
+			};
+
+			if let Some((pattern, range)) = self.string(&element, name) {
+				match Glob::new(&pattern) {
+					Ok(_) => patterns.push(pattern),
+					Err(error) => self.error(
+						range,
+						format!("Invalid glob pattern in \"{}\"", name),
+						error.kind().to_string(),
+					),
+				}
+			}
+		}
+		patterns
+	}
+
+	fn invalid_type(&mut self, value: &Expr, name: &str, expected: &str) {
+		self.error(
+			value.syntax().trimmed_range(),
+			format!("Invalid type for \"{}\"", name),
+			format!("expected {}", expected),
+		);
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::{
+		parse_configuration, Configuration, FilesConfiguration, FormatterConfiguration,
+		LoadedConfiguration,
+	};
+	use rome_analyzer::RuleLevel;
+	use rome_core::file_handlers::Language;
+	use rome_formatter::{ByteOrderMark, IndentStyle, LineEnding, QuoteStyle};
+	use rome_path::MemoryFileSystem;
+	use std::path::{Path, PathBuf};
+
+	#[test]
+	fn parses_configuration() {
+		let configuration = parse_configuration(
+			r#"{
+				// comments are allowed
+				"formatter": {
+					"indentStyle": "space",
+					"indentSize": 4,
+					"lineWidth": 100,
+					"quoteStyle": "single",
+					"lineEnding": "crlf",
+					"byteOrderMark": "remove"
+				},
+				"linter": { "enabled": false, "rules": { "noDebugger": "warn" } },
+				"json": { "formatter": { "indentStyle": "tab" } },
+				"files": { "include": ["src/**"], "ignore": ["**/*.min.js"] }
+			}"#,
+			0,
+		)
+		.unwrap();
+
+		let options = configuration
+			.formatter_for(Some(Language::Js))
+			.to_format_options();
+		assert_eq!(options.indent_style, IndentStyle::Space(4));
+		assert_eq!(options.line_width, 100);
+		assert_eq!(options.quote_style, QuoteStyle::Single);
+		assert_eq!(options.line_ending, LineEnding::CarriageReturnLineFeed);
+		assert_eq!(options.byte_order_mark, ByteOrderMark::Remove);
+
+		let options = configuration
+			.formatter_for(Some(Language::Json))
+			.to_format_options();
+		assert_eq!(options.indent_style, IndentStyle::Tab);
+		assert_eq!(options.line_width, 100);
+
+		assert!(!configuration.linter.enabled);
+		assert_eq!(
+			configuration.linter.rules.level("noDebugger"),
+			Some(RuleLevel::Warn)
+		);
+		assert_eq!(configuration.linter.rules.level("noDoubleEquals"), None);
+
+		assert_eq!(configuration.files.include, vec!["src/**"]);
+		assert_eq!(configuration.files.ignore, vec!["**/*.min.js"]);
+	}
+
+	#[test]
+	fn arguments_override_configuration() {
+		let configuration = parse_configuration(
+			r#"{ "formatter": { "indentStyle": "space", "lineWidth": 100 } }"#,
+			0,
+		)
+		.unwrap();
+		let arguments = FormatterConfiguration {
+			line_width: Some(40),
+			..FormatterConfiguration::default()
+		};
+
+		let mut formatter = configuration.formatter_for(Some(Language::Js));
+		formatter.merge(&arguments);
+		let options = formatter.to_format_options();
+		assert_eq!(options.indent_style, IndentStyle::Space(2));
+		assert_eq!(options.line_width, 40);
+	}
+
+	#[test]
+	fn reports_invalid_configuration() {
+		let content = r#"{
+			"formatter": { "lineWidth": "80", "quoteStyle": "backtick" },
+			"linter": { "rules": { "noDebugger": "on", "noConsole": "off" } },
+			"plugins": []
+		}"#;
+		let diagnostics = parse_configuration(content, 0).unwrap_err();
+		let errors: Vec<_> = diagnostics
+			.iter()
+			.map(|diagnostic| {
+				let range = diagnostic.primary.as_ref().unwrap().span.range.clone();
+				(diagnostic.title.as_str(), &content[range])
+			})
+			.collect();
+
+		assert_eq!(
+			errors,
+			vec![
+				("Invalid type for \"lineWidth\"", "\"80\""),
+				("Invalid value for \"quoteStyle\"", "\"backtick\""),
+				("Invalid value for \"noDebugger\"", "\"on\""),
+				("Unknown rule \"noConsole\"", "\"noConsole\""),
+				("Unknown key \"plugins\"", "\"plugins\""),
+			]
+		);
+	}
+
+	#[test]
+	fn filters_files() {
+		let fs = MemoryFileSystem::default()
+			.with_file("project/rome.json", "{}")
+			.with_file("project/src/index.js", "")
+			.with_file("project/src/generated/a.js", "")
+			.with_file("project/scripts/build.js", "")
+			.with_file("other/index.js", "");
+		let loaded = LoadedConfiguration {
+			path: PathBuf::from("project/rome.json"),
+			configuration: Configuration {
+				files: FilesConfiguration {
+					include: vec!["src/**".to_string()],
+					ignore: vec!["src/generated/**".to_string()],
+				},
+				..Configuration::default()
+			},
+		};
+
+		let filter = loaded.files_filter(&fs, true);
+		let is_included = |path: &str| filter.is_included(&fs, Path::new(path));
+		assert!(is_included("project/src/index.js"));
+		assert!(is_included("project/scripts/../src/./index.js"));
+		assert!(!is_included("project/src/generated/a.js"));
+		assert!(!is_included("project/scripts/build.js"));
+		// the files outside of the project are always processed
+		assert!(is_included("other/index.js"));
+
+		let filter = loaded.files_filter(&fs, false);
+		assert!(filter.is_included(&fs, Path::new("project/src/generated/a.js")));
+	}
+}
@@ -0,0 +1,383 @@
+//! Line based diff between the content of a file and its formatted version, printed as unified diff.
+//!
+//! The diff is computed with the linear space variant of the
+//! [Myers algorithm](http://www.xmailserver.org/diff2.pdf), which splits the sequences at the
+//! middle of the shortest edit script and diffs both halves recursively.
+use rslint_errors::termcolor::{Color, ColorSpec, WriteColor};
+use std::fmt::Write;
+use std::io;
+
+/// Number of unchanged lines printed around each change
+const CONTEXT_LINES: usize = 3;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum LineChange {
+	Equal,
+	Delete,
+	Insert,
+}
+
+/// A line of the diff, along with the kind of change
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) struct DiffLine<'a> {
+	pub(crate) change: LineChange,
+	pub(crate) text: &'a str,
+}
+
+/// Computes the shortest list of changes that transforms `old` into `new`
+pub(crate) fn diff_lines<'a>(old: &'a str, new: &'a str) -> Vec<DiffLine<'a>> {
+	let old: Vec<&str> = old.split_inclusive('\n').collect();
+	let new: Vec<&str> = new.split_inclusive('\n').collect();
+
+	let mut lines = Vec::with_capacity(old.len().max(new.len()));
+	diff_range(&old, &new, &mut lines);
+	lines
+}
+
+/// Pushes the changes that transform `old` into `new` to `lines`
+fn diff_range<'a>(old: &[&'a str], new: &[&'a str], lines: &mut Vec<DiffLine<'a>>) {
+	let prefix = old
+		.iter()
+		.zip(new)
+		.take_while(|(old, new)| old == new)
+		.count();
+	push_lines(lines, LineChange::Equal, &old[..prefix]);
+	let (old, new) = (&old[prefix..], &new[prefix..]);
+
+	let suffix = old
+		.iter()
+		.rev()
+		.zip(new.iter().rev())
+		.take_while(|(old, new)| old == new)
+		.count();
+	let (old, suffix_lines) = old.split_at(old.len() - suffix);
+	let new = &new[..new.len() - suffix];
+
+	if old.is_empty() {
+		push_lines(lines, LineChange::Insert, new);
+	} else if new.is_empty() {
+		push_lines(lines, LineChange::Delete, old);
+	} else {
+		// both halves have fewer changes than the whole, as the first and the last lines differ
+		let snake = middle_snake(old, new);
+		diff_range(&old[..snake.start.0], &new[..snake.start.1], lines);
+		push_lines(lines, LineChange::Equal, &old[snake.start.0..snake.end.0]);
+		diff_range(&old[snake.end.0..], &new[snake.end.1..], lines);
+	}
+
+	push_lines(lines, LineChange::Equal, suffix_lines);
+}
+
+fn push_lines<'a>(lines: &mut Vec<DiffLine<'a>>, change: LineChange, texts: &[&'a str]) {
+	lines.extend(texts.iter().map(|text| DiffLine { change, text }));
+}
+
+/// A run of equal lines, from the positions `start` to `end` in the old and the new lines
+struct Snake {
+	start: (usize, usize),
+	end: (usize, usize),
+}
+
+/// Finds the snake in the middle of the shortest edit script, by running the Myers algorithm
+/// forward from the start and backward from the end until the two paths overlap.
+///
+/// Only the furthest reaching points of the last explored edit distance are kept, so the memory
+/// is linear in the number of lines
+fn middle_snake(old: &[&str], new: &[&str]) -> Snake {
+	let (n, m) = (old.len() as isize, new.len() as isize);
+	let delta = n - m;
+	let is_odd = delta % 2 != 0;
+	let max = (n + m + 1) / 2;
+	// the diagonal `k` is stored at `k + offset`, the diagonals `-max - 1` and `max + 1` are read
+	let offset = max + 1;
+	let mut forward = vec![0isize; 2 * offset as usize + 1];
+	// the backward furthest reaching points are counted from the end of the lines
+	let mut backward = vec![0isize; 2 * offset as usize + 1];
+
+	for d in 0..=max {
+		for k in (-d..=d).step_by(2) {
+			let mut x = furthest_x(&forward, k, d, offset);
+			let mut y = x - k;
+			let (start_x, start_y) = (x, y);
+			while x < n && y < m && old[x as usize] == new[y as usize] {
+				x += 1;
+				y += 1;
+			}
+			forward[(k + offset) as usize] = x;
+
+			// the backward paths have explored the edit distance `d - 1`
+			let backward_k = delta - k;
+			if is_odd && backward_k.abs() < d && x + backward[(backward_k + offset) as usize] >= n {
+				return Snake {
+					start: (start_x as usize, start_y as usize),
+					end: (x as usize, y as usize),
+				};
+			}
+		}
+
+		for k in (-d..=d).step_by(2) {
+			let mut x = furthest_x(&backward, k, d, offset);
+			let mut y = x - k;
+			let (start_x, start_y) = (x, y);
+			while x < n && y < m && old[(n - x - 1) as usize] == new[(m - y - 1) as usize] {
+				x += 1;
+				y += 1;
+			}
+			backward[(k + offset) as usize] = x;
+
+			// the forward paths have explored the edit distance `d`
+			let forward_k = delta - k;
+			if !is_odd && forward_k.abs() <= d && x + forward[(forward_k + offset) as usize] >= n {
+				return Snake {
+					start: ((n - x) as usize, (m - y) as usize),
+					end: ((n - start_x) as usize, (m - start_y) as usize),
+				};
+			}
+		}
+	}
+
+	unreachable!("the paths overlap once half of the edit distance has been explored")
+}
+
+/// The furthest point of the diagonal `k` reached with the edit distance `d`, before following
+/// the equal lines
+fn furthest_x(v: &[isize], k: isize, d: isize, offset: isize) -> isize {
+	let above = v[(k + 1 + offset) as usize];
+	let below = v[(k - 1 + offset) as usize];
+	if k == -d || (k != d && below < above) {
+		above
+	} else {
+		below + 1
+	}
+}
+
+/// Writes the changes between `old` and `new` as an unified diff, without colors. The diff is
+/// colored by [print_diff] when it's printed in a terminal
+pub(crate) fn unified_diff(file_name: &str, old: &str, new: &str) -> String {
+	let lines = diff_lines(old, new);
+	let mut output = format!("--- {}\n+++ {}\n", file_name, file_name);
+	for hunk in hunks(&lines) {
+		write_hunk(&mut output, &lines, hunk);
+	}
+	output
+}
+
+/// Prints an unified diff created by [unified_diff], where the deleted lines are red and the
+/// inserted lines are green
+pub(crate) fn print_diff(writer: &mut dyn WriteColor, diff: &str) -> io::Result<()> {
+	for line in diff.lines() {
+		let mut spec = ColorSpec::new();
+		if line.starts_with("--- ") || line.starts_with("+++ ") {
+			spec.set_bold(true);
+		} else if line.starts_with("@@") {
+			spec.set_fg(Some(Color::Cyan));
+		} else if line.starts_with('-') {
+			spec.set_fg(Some(Color::Red));
+		} else if line.starts_with('+') {
+			spec.set_fg(Some(Color::Green));
+		}
+
+		writer.set_color(&spec)?;
+		write!(writer, "{}", line)?;
+		writer.reset()?;
+		writeln!(writer)?;
+	}
+	Ok(())
+}
+
+/// A range of diff lines that are printed together, along with their
+/// starting line in the old and new content
+struct Hunk {
+	start: usize,
+	end: usize,
+	old_line: usize,
+	new_line: usize,
+}
+
+fn hunks(lines: &[DiffLine]) -> Vec<Hunk> {
+	let mut hunks: Vec<Hunk> = Vec::new();
+	let mut old_line = 0;
+	let mut new_line = 0;
+
+	for (index, line) in lines.iter().enumerate() {
+		if line.change != LineChange::Equal {
+			let start = index.saturating_sub(CONTEXT_LINES);
+			let end = (index + CONTEXT_LINES + 1).min(lines.len());
+
+			match hunks.last_mut() {
+				Some(hunk) if hunk.end >= start => hunk.end = end,
+				_ => {
+					// lines between `start` and `index` are always equal lines
+					let context = index - start;
+					hunks.push(Hunk {
+						start,
+						end,
+						old_line: old_line - context,
+						new_line: new_line - context,
+					})
+				}
+			}
+		}
+
+		match line.change {
+			LineChange::Equal => {
+				old_line += 1;
+				new_line += 1;
+			}
+			LineChange::Delete => old_line += 1,
+			LineChange::Insert => new_line += 1,
+		}
+	}
+
+	hunks
+}
+
+// writing in a string never fails
+fn write_hunk(output: &mut String, lines: &[DiffLine], hunk: Hunk) {
+	let lines = &lines[hunk.start..hunk.end];
+	let old_count = lines
+		.iter()
+		.filter(|line| line.change != LineChange::Insert)
+		.count();
+	let new_count = lines
+		.iter()
+		.filter(|line| line.change != LineChange::Delete)
+		.count();
+
+	writeln!(
+		output,
+		"@@ -{} +{} @@",
+		hunk_range(hunk.old_line, old_count),
+		hunk_range(hunk.new_line, new_count)
+	)
+	.unwrap();
+
+	for line in lines {
+		let prefix = match line.change {
+			LineChange::Equal => ' ',
+			LineChange::Delete => '-',
+			LineChange::Insert => '+',
+		};
+		writeln!(
+			output,
+			"{}{}",
+			prefix,
+			line.text.trim_end_matches(&['\r', '\n'][..])
+		)
+		.unwrap();
+		if !line.text.ends_with('\n') {
+			output.push_str("\\ No newline at end of file\n");
+		}
+	}
+}
+
+/// Formats the range of a hunk header, where `start` is zero based
+fn hunk_range(start: usize, count: usize) -> String {
+	if count == 0 {
+		format!("{},0", start)
+	} else if count == 1 {
+		format!("{}", start + 1)
+	} else {
+		format!("{},{}", start + 1, count)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::{diff_lines, print_diff, unified_diff, LineChange};
+	use rslint_errors::termcolor::{Ansi, NoColor};
+
+	#[test]
+	fn diff_changed_lines() {
+		let changes: Vec<_> = diff_lines("a\nb\nc\n", "a\nd\nc\n")
+			.into_iter()
+			.map(|line| (line.change, line.text))
+			.collect();
+
+		assert_eq!(
+			changes,
+			vec![
+				(LineChange::Equal, "a\n"),
+				(LineChange::Delete, "b\n"),
+				(LineChange::Insert, "d\n"),
+				(LineChange::Equal, "c\n"),
+			]
+		);
+	}
+
+	#[test]
+	fn diff_identical_content() {
+		let lines = diff_lines("a\nb\n", "a\nb\n");
+		assert!(lines.iter().all(|line| line.change == LineChange::Equal));
+		assert_eq!(lines.len(), 2);
+	}
+
+	#[test]
+	fn diff_as_unified_diff() {
+		let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\nlet a  =  1";
+		let new = "1\n2\n3\n4\n5\n6\n7\n8\n9\nlet a = 1;\n";
+		assert_eq!(
+			unified_diff("file.js", old, new),
+			"--- file.js
++++ file.js
+@@ -7,4 +7,4 @@
+ 7
+ 8
+ 9
+-let a  =  1
+\\ No newline at end of file
++let a = 1;
+"
+		);
+	}
+
+	#[test]
+	fn print_colored_diff() {
+		let diff = unified_diff("file.js", "let a  =  1\n", "let a = 1;\n");
+
+		let mut colored = Ansi::new(Vec::new());
+		print_diff(&mut colored, &diff).unwrap();
+		let colored = String::from_utf8(colored.into_inner()).unwrap();
+		assert!(colored.contains("\x1b[1m--- file.js\x1b[0m\n"));
+		assert!(colored.contains("\x1b[36m@@ -1 +1 @@\x1b[0m\n"));
+		assert!(colored.contains("\x1b[31m-let a  =  1\x1b[0m\n"));
+		assert!(colored.contains("\x1b[32m+let a = 1;\x1b[0m\n"));
+
+		let mut plain = NoColor::new(Vec::new());
+		print_diff(&mut plain, &diff).unwrap();
+		assert_eq!(String::from_utf8(plain.into_inner()).unwrap(), diff);
+	}
+
+	#[test]
+	fn diff_moved_lines() {
+		let changes: Vec<_> = diff_lines("a\nb\nc\nd\n", "c\na\nb\ne\n")
+			.into_iter()
+			.map(|line| (line.change, line.text))
+			.collect();
+
+		assert_eq!(
+			changes,
+			vec![
+				(LineChange::Insert, "c\n"),
+				(LineChange::Equal, "a\n"),
+				(LineChange::Equal, "b\n"),
+				(LineChange::Delete, "c\n"),
+				(LineChange::Delete, "d\n"),
+				(LineChange::Insert, "e\n"),
+			]
+		);
+	}
+
+	#[test]
+	fn diff_large_content() {
+		// every line changes, which is the worst case of the algorithm
+		let old: String = (0..5000).map(|line| format!("line {}\r\n", line)).collect();
+		let new = old.replace("\r\n", "\n");
+		let lines = diff_lines(&old, &new);
+
+		let count = |change| lines.iter().filter(|line| line.change == change).count();
+		assert_eq!(count(LineChange::Delete), 5000);
+		assert_eq!(count(LineChange::Insert), 5000);
+		assert_eq!(count(LineChange::Equal), 0);
+	}
+}
@@ -0,0 +1,281 @@
+//! Resolves the [EditorConfig](https://editorconfig.org) properties that apply to a file.
+//!
+//! The `.editorconfig` files are searched in the directory of the file and then in its
+//! ancestors, until a file that has `root = true`. The sections of the files closer to the
+//! file take precedence, and inside a file the sections that come later take precedence.
+//!
+//! The supported properties are `indent_style`, `indent_size`, `tab_width`, `end_of_line`
+//! and `max_line_length`.
+use crate::configuration::FormatterConfiguration;
+use globset::{GlobBuilder, GlobMatcher};
+use rome_path::{FileSystem, RomePath};
+use std::collections::HashMap;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+pub(crate) const EDITORCONFIG_FILE_NAME: &str = ".editorconfig";
+
+/// The properties of a section, lowercased
+type Properties = HashMap<String, String>;
+
+/// A section of an `.editorconfig` file
+#[derive(Debug)]
+struct Section {
+	/// [None] when the glob of the section is invalid, in which case the section never applies
+	matcher: Option<GlobMatcher>,
+	properties: Vec<(String, String)>,
+}
+
+/// The content of an `.editorconfig` file
+#[derive(Debug, Default)]
+pub(crate) struct EditorConfigFile {
+	root: bool,
+	sections: Vec<Section>,
+}
+
+impl EditorConfigFile {
+	pub(crate) fn parse(content: &str) -> Self {
+		let mut file = EditorConfigFile::default();
+
+		for line in content.lines().map(str::trim) {
+			if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+				continue;
+			}
+
+			if line.starts_with('[') && line.ends_with(']') {
+				file.sections.push(Section {
+					matcher: section_matcher(&line[1..line.len() - 1]),
+					properties: Vec::new(),
+				});
+				continue;
+			}
+
+			let (key, value) = match line.split_once('=') {
+				Some((key, value)) => (key.trim().to_lowercase(), value.trim().to_lowercase()),
+				None => continue,
+			};
+			match file.sections.last_mut() {
+				Some(section) => section.properties.push((key, value)),
+				// the preamble, before the first section
+				None => {
+					if key == "root" {
+						file.root = value == "true";
+					}
+				}
+			}
+		}
+
+		file
+	}
+
+	/// Adds the properties of the sections that match `path`, relative to the
+	/// directory of the `.editorconfig` file
+	fn collect_properties(&self, path: &Path, properties: &mut Properties) {
+		let sections = self.sections.iter().filter(|section| {
+			section
+				.matcher
+				.as_ref()
+				.map_or(false, |matcher| matcher.is_match(path))
+		});
+
+		for section in sections {
+			for (key, value) in &section.properties {
+				if value == "unset" {
+					properties.remove(key);
+				} else {
+					properties.insert(key.clone(), value.clone());
+				}
+			}
+		}
+	}
+
+	/// Resolves the options of the formatter for `path`, relative to the directory of the
+	/// `.editorconfig` file, taking into account only this file
+	pub(crate) fn resolve(&self, path: &Path) -> FormatterConfiguration {
+		let mut properties = Properties::new();
+		self.collect_properties(path, &mut properties);
+		formatter_configuration(&properties)
+	}
+}
+
+/// Creates the matcher of the glob of a section.
+///
+/// A glob without `/` matches the files with that name in any directory, otherwise it's
+/// relative to the directory of the `.editorconfig` file
+fn section_matcher(glob: &str) -> Option<GlobMatcher> {
+	let glob = if let Some(glob) = glob.strip_prefix('/') {
+		glob.to_string()
+	} else if glob.contains('/') {
+		glob.to_string()
+	} else {
+		format!("**/{}", glob)
+	};
+
+	// `**` matches any string, while globset only supports it as a whole component
+	let mut expanded = String::new();
+	let mut rest = glob.as_str();
+	while let Some(index) = rest.find("**") {
+		expanded.push_str(&rest[..index + 2]);
+		rest = &rest[index + 2..];
+		if !rest.is_empty() && !rest.starts_with('/') {
+			expanded.push_str("/*");
+		}
+	}
+	expanded.push_str(rest);
+
+	GlobBuilder::new(&expanded)
+		.literal_separator(true)
+		.build()
+		.ok()
+		.map(|glob| glob.compile_matcher())
+}
+
+/// Maps the EditorConfig properties onto the options of the formatter
+fn formatter_configuration(properties: &Properties) -> FormatterConfiguration {
+	let property = |key: &str| properties.get(key).map(String::as_str);
+
+	let indent_size = property("indent_size").and_then(|size| size.parse::<u8>().ok());
+	// when not set, the width of the tab is the size of the indentation
+	let tab_width = property("tab_width")
+		.and_then(|width| width.parse::<u8>().ok())
+		.or(indent_size);
+
+	FormatterConfiguration {
+		indent_style: property("indent_style").and_then(|style| style.parse().ok()),
+		indent_size: match property("indent_size") {
+			Some("tab") => tab_width,
+			_ => indent_size,
+		},
+		tab_width,
+		// "off" is the only value that isn't a number
+		line_width: property("max_line_length").and_then(|width| width.parse().ok()),
+		line_ending: property("end_of_line").and_then(|ending| ending.parse().ok()),
+		..FormatterConfiguration::default()
+	}
+}
+
+/// Resolves the properties of the files, caching the `.editorconfig` files that have been read
+#[derive(Debug, Default)]
+pub(crate) struct EditorConfig {
+	/// The `.editorconfig` file of each directory, [None] if the directory doesn't have one
+	files: Mutex<HashMap<PathBuf, Option<Arc<EditorConfigFile>>>>,
+}
+
+impl EditorConfig {
+	/// Resolves the options of the formatter for the file, the `.editorconfig` files are read
+	/// from the file system of the file
+	pub(crate) fn resolve(&self, rome_path: &RomePath) -> FormatterConfiguration {
+		let fs = rome_path.file_system();
+		let path = match fs.canonicalize(rome_path) {
+			Ok(path) => path,
+			// the file may not exist, like the path given to format stdin
+			Err(_) => match env::current_dir() {
+				Ok(directory) => directory.join(rome_path.as_path()),
+				Err(_) => return FormatterConfiguration::default(),
+			},
+		};
+
+		let mut files = Vec::new();
+		for directory in path.ancestors().skip(1) {
+			if let Some(file) = self.load(fs, directory) {
+				let is_root = file.root;
+				files.push((directory, file));
+				if is_root {
+					break;
+				}
+			}
+		}
+
+		// the files closer to the root are applied first, so the others can override them
+		let mut properties = Properties::new();
+		for (directory, file) in files.iter().rev() {
+			if let Ok(relative) = path.strip_prefix(directory) {
+				file.collect_properties(relative, &mut properties);
+			}
+		}
+
+		formatter_configuration(&properties)
+	}
+
+	fn load(&self, fs: &dyn FileSystem, directory: &Path) -> Option<Arc<EditorConfigFile>> {
+		let mut files = self.files.lock().unwrap();
+		files
+			.entry(directory.to_path_buf())
+			.or_insert_with(|| {
+				RomePath::new(directory.join(EDITORCONFIG_FILE_NAME))
+					.with_file_system(fs)
+					.read_to_string()
+					.ok()
+					.map(|content| Arc::new(EditorConfigFile::parse(&content)))
+			})
+			.clone()
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::{EditorConfig, EditorConfigFile};
+	use rome_formatter::{IndentStyle, LineEnding};
+	use rome_path::{MemoryFileSystem, RomePath};
+	use std::path::Path;
+
+	#[test]
+	fn matches_sections() {
+		let file = EditorConfigFile::parse(
+			"root = true
+
+[*]
+indent_style = space
+indent_size = 4
+end_of_line = crlf
+
+[*.{json,md}]
+indent_size = 2
+
+[lib/**.js]
+indent_style = tab
+tab_width = 8
+max_line_length = 100
+",
+		);
+
+		let formatter = file.resolve(Path::new("src/index.js"));
+		assert_eq!(formatter.indent_style, Some(IndentStyle::Space(2)));
+		assert_eq!(formatter.indent_size, Some(4));
+		assert_eq!(formatter.tab_width, Some(4));
+		assert_eq!(
+			formatter.line_ending,
+			Some(LineEnding::CarriageReturnLineFeed)
+		);
+
+		let formatter = file.resolve(Path::new("src/package.json"));
+		assert_eq!(formatter.indent_size, Some(2));
+
+		let formatter = file.resolve(Path::new("lib/nested/index.js"));
+		assert_eq!(formatter.indent_style, Some(IndentStyle::Tab));
+		assert_eq!(formatter.tab_width, Some(8));
+		assert_eq!(formatter.line_width, Some(100));
+	}
+
+	#[test]
+	fn stops_at_root() {
+		let fs = MemoryFileSystem::default()
+			.with_file(".editorconfig", "[*]\nmax_line_length = 120\n")
+			.with_file(
+				"project/.editorconfig",
+				"root = true\n[*]\nindent_size = 4\n",
+			)
+			.with_file(
+				"project/src/.editorconfig",
+				"[*.js]\nindent_style = space\n",
+			)
+			.with_file("project/src/index.js", "");
+
+		let file = RomePath::new("project/src/index.js").with_file_system(&fs);
+		let formatter = EditorConfig::default().resolve(&file);
+		assert_eq!(formatter.indent_style, Some(IndentStyle::Space(2)));
+		assert_eq!(formatter.indent_size, Some(4));
+		assert_eq!(formatter.line_width, None);
+	}
+}
@@ -0,0 +1,272 @@
+//! Queries the git repository of the working directory, by running the `git` binary.
+//!
+//! It's used to process only the files that have been changed, or the content that has been staged,
+//! which is what a pre-commit hook needs.
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// The repository that contains the working directory
+pub(crate) struct Repository {
+	/// The absolute path of the root of the working tree
+	root: PathBuf,
+}
+
+impl Repository {
+	pub(crate) fn discover() -> Result<Self, String> {
+		let output = run_git(None, &["rev-parse", "--show-toplevel"], None)
+			.map_err(|error| format!("Cannot find the git repository: {}", error))?;
+		let root = String::from_utf8_lossy(&output).trim_end().to_string();
+		Ok(Self {
+			root: PathBuf::from(root),
+		})
+	}
+
+	/// The files that differ from the revision `base`, along with the untracked files that
+	/// aren't ignored. The deleted files are excluded
+	pub(crate) fn changed_files(&self, base: &str) -> Result<Vec<PathBuf>, String> {
+		let commit = self.resolve_commit(base)?;
+		let mut files = self.list_files(&[
+			"diff",
+			"--name-only",
+			"-z",
+			"--diff-filter=d",
+			&commit,
+			"--",
+		])?;
+		files.extend(self.list_files(&[
+			"ls-files",
+			"--others",
+			"--exclude-standard",
+			"--full-name",
+			"-z",
+		])?);
+		Ok(files)
+	}
+
+	/// Resolves `revision` to the name of a commit. The revision is passed by the user, so it's
+	/// never read as an option of git
+	fn resolve_commit(&self, revision: &str) -> Result<String, String> {
+		let invalid = || format!("The revision \"{}\" isn't a commit", revision);
+		if revision.starts_with('-') {
+			return Err(invalid());
+		}
+
+		let output = self
+			.git(
+				&[
+					"rev-parse",
+					"--verify",
+					"--quiet",
+					"--end-of-options",
+					&format!("{}^{{commit}}", revision),
+				],
+				None,
+			)
+			.map_err(|_| invalid())?;
+		Ok(String::from_utf8_lossy(&output).trim_end().to_string())
+	}
+
+	/// The files that have been added or modified in the index. The deleted files are excluded
+	pub(crate) fn staged_files(&self) -> Result<Vec<PathBuf>, String> {
+		self.list_files(&["diff", "--cached", "--name-only", "-z", "--diff-filter=d"])
+	}
+
+	/// Reads the content of the file that has been staged
+	pub(crate) fn read_staged(&self, path: &Path) -> Result<String, String> {
+		let name = self.index_name(path)?;
+		let content = self.git(&["cat-file", "blob", &format!(":{}", name)], None)?;
+		String::from_utf8(content)
+			.map_err(|_| format!("The staged content of \"{}\" isn't UTF-8", path.display()))
+	}
+
+	/// Replaces the staged content of the file with `content`, keeping the mode of the file.
+	/// The working tree isn't modified
+	pub(crate) fn stage(&self, path: &Path, content: &str) -> Result<(), String> {
+		let name = self.index_name(path)?;
+
+		// the entry has the form "<mode> <object> <stage>\t<file>"
+		let entry = self.git(&["ls-files", "--stage", "-z", "--", &name], None)?;
+		let entry = String::from_utf8_lossy(&entry);
+		let mode = match entry.split(' ').next() {
+			Some(mode) if !mode.is_empty() => mode.to_string(),
+			_ => return Err(format!("The file \"{}\" isn't staged", path.display())),
+		};
+
+		let object = self.git(
+			&["hash-object", "-w", "--no-filters", "--stdin"],
+			Some(content.as_bytes()),
+		)?;
+		let object = String::from_utf8_lossy(&object).trim_end().to_string();
+
+		self.git(
+			&[
+				"update-index",
+				"--cacheinfo",
+				&format!("{},{},{}", mode, object, name),
+			],
+			None,
+		)?;
+		Ok(())
+	}
+
+	/// The path of the file relative to the root, as used by the index
+	fn index_name(&self, path: &Path) -> Result<String, String> {
+		path.canonicalize()
+			.ok()
+			.and_then(|path| {
+				path.strip_prefix(&self.root)
+					.ok()
+					.map(|relative| relative.to_string_lossy().replace('\\', "/"))
+			})
+			.ok_or_else(|| {
+				format!(
+					"The file \"{}\" is outside of the git repository",
+					path.display()
+				)
+			})
+	}
+
+	/// Runs a git command that prints a list of paths, relative to the root, separated by NUL
+	fn list_files(&self, args: &[&str]) -> Result<Vec<PathBuf>, String> {
+		let output = self.git(args, None)?;
+		Ok(output
+			.split(|byte| *byte == 0)
+			.filter(|name| !name.is_empty())
+			.map(|name| self.root.join(String::from_utf8_lossy(name).as_ref()))
+			.collect())
+	}
+
+	fn git(&self, args: &[&str], input: Option<&[u8]>) -> Result<Vec<u8>, String> {
+		run_git(Some(&self.root), args, input)
+	}
+}
+
+/// Runs git, returning what it printed on stdout
+fn run_git(
+	directory: Option<&Path>,
+	args: &[&str],
+	input: Option<&[u8]>,
+) -> Result<Vec<u8>, String> {
+	let mut command = Command::new("git");
+	command
+		.args(args)
+		.stdin(if input.is_some() {
+			Stdio::piped()
+		} else {
+			Stdio::null()
+		})
+		.stdout(Stdio::piped())
+		.stderr(Stdio::piped());
+	if let Some(directory) = directory {
+		command.current_dir(directory);
+	}
+
+	let mut child = command
+		.spawn()
+		.map_err(|error| format!("cannot run git: {}", error))?;
+	if let Some(input) = input {
+		// the handle is dropped right after, so git sees the end of the input
+		child
+			.stdin
+			.take()
+			.unwrap()
+			.write_all(input)
+			.map_err(|error| format!("cannot write to \"git {}\": {}", args.join(" "), error))?;
+	}
+
+	let output = child
+		.wait_with_output()
+		.map_err(|error| format!("cannot run git: {}", error))?;
+	if output.status.success() {
+		Ok(output.stdout)
+	} else {
+		Err(format!(
+			"\"git {}\" failed: {}",
+			args.join(" "),
+			String::from_utf8_lossy(&output.stderr).trim_end()
+		))
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::Repository;
+	use rome_path::testing::TestDirectory;
+	use std::fs;
+	use std::path::Path;
+	use std::process::Command;
+
+	fn git(directory: &Path, args: &[&str]) {
+		let status = Command::new("git")
+			.args(args)
+			.current_dir(directory)
+			.status()
+			.unwrap();
+		assert!(status.success());
+	}
+
+	#[test]
+	fn validates_base() {
+		let test_directory = TestDirectory::new("git_validates_base");
+		let directory = test_directory.canonicalize().unwrap();
+		git(&directory, &["init", "--quiet"]);
+
+		let file = directory.join("index.js");
+		fs::write(&file, "let a = 1;\n").unwrap();
+		git(&directory, &["add", "index.js"]);
+		git(
+			&directory,
+			&[
+				"-c",
+				"user.name=Rome",
+				"-c",
+				"user.email=rome@example.com",
+				"commit",
+				"--quiet",
+				"--message=Initial commit",
+			],
+		);
+		fs::write(&file, "let a = 2;\n").unwrap();
+
+		let repository = Repository {
+			root: directory.clone(),
+		};
+		assert_eq!(repository.changed_files("HEAD").unwrap(), vec![file]);
+
+		// the options of git aren't accepted as revision
+		let output = directory.join("output.txt");
+		let option = format!("--output={}", output.display());
+		assert!(repository.changed_files(&option).is_err());
+		assert!(!output.exists());
+		assert!(repository.changed_files("missing").is_err());
+		assert!(repository.changed_files("HEAD:index.js").is_err());
+	}
+
+	#[test]
+	fn stages_content() {
+		let test_directory = TestDirectory::new("git_stages_content");
+		let directory = test_directory.canonicalize().unwrap();
+		git(&directory, &["init", "--quiet"]);
+
+		let file = directory.join("index.js");
+		fs::write(&file, "let a  =  1\n").unwrap();
+		git(&directory, &["add", "index.js"]);
+		fs::write(&file, "let a  =  1\nlet b  =  2\n").unwrap();
+		fs::write(directory.join("new.js"), "").unwrap();
+
+		let repository = Repository {
+			root: directory.clone(),
+		};
+		assert_eq!(repository.staged_files().unwrap(), vec![file.clone()]);
+		assert_eq!(repository.read_staged(&file).unwrap(), "let a  =  1\n");
+
+		repository.stage(&file, "let a = 1;\n").unwrap();
+		assert_eq!(repository.read_staged(&file).unwrap(), "let a = 1;\n");
+		// the unstaged changes are kept
+		assert_eq!(
+			fs::read_to_string(&file).unwrap(),
+			"let a  =  1\nlet b  =  2\n"
+		);
+	}
+}
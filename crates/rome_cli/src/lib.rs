@@ -1,9 +1,19 @@
 use clap::{crate_version, App, AppSettings, Arg};
-use rome_core::create_app;
-use rome_formatter::{format_file_and_save, FormatOptions, IndentStyle};
-use rome_path::RomePath;
+use rome_formatter::{ByteOrderMark, IndentStyle, LineEnding, QuoteStyle};
 use std::{path::PathBuf, str::FromStr};
 
+mod cache;
+mod commands;
+mod configuration;
+mod diff;
+mod editorconfig;
+mod git;
+mod lsp;
+mod reporter;
+mod runner;
+mod traversal;
+mod watch;
+
 /// Main function to run Rome CLI
 pub fn run_cli() {
 	let matches = App::new("rome")
@@ -12,21 +22,20 @@ pub fn run_cli() {
 		.setting(AppSettings::SubcommandRequiredElseHelp)
 		.subcommand(
 			App::new("format")
-				.about("Format a file")
+				.about("Format files, directories and glob patterns")
+				.after_help("The formatter doesn't support TypeScript, JSX and ECMAScript modules yet: the .ts, .tsx, .mts, .cts, .jsx and .mjs files are skipped")
 				.arg(
 					Arg::new("indent_style")
 						.long("indent-style")
-						.about("The style of indentation")
+						.about("The style of indentation. Defaults to tab")
 						.value_name("tab|space")
-						.default_value("tab")
 						.validator(|value| IndentStyle::from_str(value).map(|_| ())),
 				)
 				.arg(
 					Arg::new("indent_size")
 						.long("indent-size")
-						.about("The size of the indent.")
+						.about("The size of the indent, when using spaces. Defaults to 2")
 						.value_name("NUMBER")
-						.default_value("2")
 						.validator(|value| {
 							value
 								.parse::<u8>()
@@ -34,16 +43,149 @@ pub fn run_cli() {
 						}),
 				)
 				.arg(
-					Arg::new("input")
-						.about("File to format")
-						.required(true)
-						.validator(|value| {
-							let path = PathBuf::from(&value);
-							if !path.exists() {
-								return Err(format!("The file \"{}\" doesn't exist.", value));
-							}
-							Ok(())
+					Arg::new("line_width")
+						.long("line-width")
+						.about("The maximum width of a line. Defaults to 80")
+						.value_name("NUMBER")
+						.validator(|value| match value.parse::<u16>() {
+							Ok(width) if width > 0 => Ok(()),
+							_ => Err("Invalid line-width value. Try using a number greater than 0"),
 						}),
+				)
+				.arg(
+					Arg::new("quote_style")
+						.long("quote-style")
+						.about("The quotes of the string literals. Defaults to double")
+						.value_name("double|single")
+						.validator(|value| QuoteStyle::from_str(value).map(|_| ())),
+				)
+				.arg(
+					Arg::new("line_ending")
+						.long("line-ending")
+						.about("The line ending of the formatted files. Defaults to lf")
+						.value_name("lf|crlf|cr")
+						.validator(|value| LineEnding::from_str(value).map(|_| ())),
+				)
+				.arg(
+					Arg::new("byte_order_mark")
+						.long("byte-order-mark")
+						.about("Whether the formatted files keep their byte order mark. Defaults to keep")
+						.value_name("keep|remove")
+						.validator(|value| ByteOrderMark::from_str(value).map(|_| ())),
+				)
+				.arg(
+					Arg::new("check")
+						.long("check")
+						.about("Print the difference with the formatted code instead of writing the files. Exits with a non-zero code if any file isn't formatted"),
+				)
+				.arg(threads_arg())
+				.arg(no_ignore_arg())
+				.arg(verbose_arg())
+				.arg(
+					Arg::new("changed")
+						.long("changed")
+						.about("Only process the files that differ from the git revision REF, HEAD by default, along with the untracked files")
+						.value_name("REF")
+						.min_values(0)
+						.require_equals(true)
+						.default_missing_value("HEAD"),
+				)
+				.arg(
+					Arg::new("staged")
+						.long("staged")
+						.about("Only process the content staged in git, and stage the formatted content. The files that have unstaged changes aren't updated in the working tree")
+						.conflicts_with("changed"),
+				)
+				.arg(no_cache_arg())
+				.arg(cache_dir_arg())
+				.arg(
+					Arg::new("watch")
+						.long("watch")
+						.about("Keep running, and process the files again each time they change")
+						.conflicts_with_all(&["changed", "staged"]),
+				)
+				.arg(reporter_arg())
+				.arg(
+					Arg::new("stdin_file_path")
+						.long("stdin-file-path")
+						.about("Format the source read from stdin and print the result on stdout. The path is used to detect the language of the source")
+						.value_name("PATH")
+						.conflicts_with_all(&["input", "check", "reporter", "watch", "changed", "staged"]),
+				)
+				.arg(
+					input_arg()
+						.about("Files, directories or glob patterns to format")
+						.required_unless_present("stdin_file_path"),
+				),
+		)
+		.subcommand(
+			App::new("lint")
+				.about("Lint files, directories and glob patterns")
+				.arg(threads_arg())
+				.arg(no_ignore_arg())
+				.arg(verbose_arg())
+				.arg(reporter_arg())
+				.arg(no_cache_arg())
+				.arg(cache_dir_arg())
+				.arg(
+					Arg::new("apply")
+						.long("apply")
+						.about("Apply the safe fixes suggested by the rules and write the files"),
+				)
+				.arg(
+					Arg::new("apply_unsafe")
+						.long("apply-unsafe")
+						.about("Apply the fixes that may change the behaviour of the code as well, and write the files"),
+				)
+				.arg(
+					input_arg()
+						.about("Files, directories or glob patterns to lint")
+						.required(true),
+				),
+		)
+		.subcommand(
+			App::new("init")
+				.about("Create the configuration file rome.json in the current directory")
+				.arg(
+					Arg::new("force")
+						.long("force")
+						.about("Overwrite the configuration file if it already exists"),
+				),
+		)
+		.subcommand(
+			App::new("parse")
+				.about("Print the diagnostics of the parser for a file, or its tokens and syntax tree")
+				.arg(
+					Arg::new("tokens")
+						.long("tokens")
+						.about("Print the tokens of the file, with their ranges"),
+				)
+				.arg(
+					Arg::new("cst")
+						.long("cst")
+						.about("Print the concrete syntax tree of the file, with the kinds and ranges of the nodes"),
+				)
+				.arg(
+					Arg::new("source_type")
+						.long("source-type")
+						.about("How the file is parsed. Defaults to typescript for the TypeScript files, module for .mjs files and script for the other files")
+						.value_name("script|module|typescript")
+						.possible_values(&["script", "module", "typescript"]),
+				)
+				.arg(
+					Arg::new("input")
+						.about("The file to parse")
+						.value_name("FILE")
+						.required(true),
+				),
+		)
+		.subcommand(
+			App::new("lsp")
+				.about("Start the language server, that editors use to show the diagnostics and format the files")
+				.arg(
+					Arg::new("stdio")
+						.long("stdio")
+						.about("Communicate on stdin and stdout. It's the only transport, the flag is accepted because editors pass it"),
 				),
 		)
 		.try_get_matches();
@@ -52,27 +194,12 @@ pub fn run_cli() {
 		Err(err) => err.exit(),
 	};
 
-	let app = create_app();
-
 	match subcommand_matches {
-		Some(("format", matches)) => {
-			let size = matches.value_of("indent_size");
-			let style = matches.value_of("indent_style");
-			let input = matches.value_of("input").unwrap();
-			let options: IndentStyle = style
-				.map(|s| match s {
-					"tab" => IndentStyle::Tab,
-					"space" => {
-						let size = size.unwrap_or("2");
-						IndentStyle::Space(size.parse::<u8>().unwrap_or(2))
-					}
-					_ => IndentStyle::default(),
-				})
-				.unwrap_or_default();
-
-			let mut file = RomePath::new(input).deduce_handler(&app);
-			format_file_and_save(&mut file, FormatOptions::new(options));
-		}
+		Some(("format", matches)) => commands::format::format(matches),
+		Some(("lint", matches)) => commands::lint::lint(matches),
+		Some(("init", matches)) => commands::init::init(matches),
+		Some(("parse", matches)) => commands::parse::parse(matches),
+		Some(("lsp", matches)) => commands::lsp::lsp(matches),
 		// Thanks to the settings AppSettings::SubcommandRequiredElseHelp we should not be there
 		_ => clap::Error::with_description(
 			"Sub command not found".to_string(),
@@ -81,3 +208,61 @@ pub fn run_cli() {
 		.exit(),
 	}
 }
+
+// the arguments shared by the commands that process files
+
+fn threads_arg() -> Arg<'static> {
+	Arg::new("threads")
+		.long("threads")
+		.about("The number of threads used to process the files. Defaults to the number of CPUs")
+		.value_name("NUMBER")
+		.validator(|value| match value.parse::<usize>() {
+			Ok(threads) if threads > 0 => Ok(()),
+			_ => Err("Invalid threads value. Try using a number greater than 0"),
+		})
+}
+
+fn no_ignore_arg() -> Arg<'static> {
+	Arg::new("no_ignore")
+		.long("no-ignore")
+		.about("Don't skip the files matched by .gitignore, .ignore, .romeignore and by the ignore patterns of the configuration")
+}
+
+fn verbose_arg() -> Arg<'static> {
+	Arg::new("verbose")
+		.long("verbose")
+		.about("Print the files and directories that have been skipped")
+}
+
+fn reporter_arg() -> Arg<'static> {
+	Arg::new("reporter")
+		.long("reporter")
+		.about("How the results are printed: \"human\" (default) for a terminal, \"json\" for one JSON record per line")
+		.value_name("REPORTER")
+		.possible_values(&["human", "json"])
+}
+
+fn no_cache_arg() -> Arg<'static> {
+	Arg::new("no_cache")
+		.long("no-cache")
+		.about("Don't skip the files that the cache knows to be formatted, or to have no diagnostics, and don't update the cache")
+}
+
+fn cache_dir_arg() -> Arg<'static> {
+	Arg::new("cache_dir")
+		.long("cache-dir")
+		.about("The directory of the cache. Defaults to node_modules/.cache/rome in the project, when the project has a node_modules directory")
+		.value_name("DIR")
+		.conflicts_with("no_cache")
+}
+
+/// The files, directories or glob patterns to process, the help is set by the command
+fn input_arg() -> Arg<'static> {
+	Arg::new("input").multiple_values(true).validator(|value| {
+		let path = PathBuf::from(&value);
+		if !traversal::is_glob(value) && !path.exists() {
+			return Err(format!("The path \"{}\" doesn't exist.", value));
+		}
+		Ok(())
+	})
+}
@@ -0,0 +1,251 @@
+//! The documents opened in the editor. Their content is kept in memory, as it's usually
+//! different from the content of the file on the disk until the document is saved.
+use crate::diff::{diff_lines, LineChange};
+use lsp_types::{Range, SemanticToken, TextDocumentContentChangeEvent, TextEdit};
+use rslint_errors::file::SimpleFile;
+use rslint_errors::lsp::{byte_span_to_range, range_to_byte_span};
+use rslint_parser::semantic_tokens::{SemanticToken as ClassifiedToken, SemanticTokenType};
+
+pub(crate) struct Document {
+	/// The version of the content, incremented by the editor on each change
+	pub(crate) version: i32,
+	pub(crate) text: String,
+}
+
+impl Document {
+	pub(crate) fn new(version: i32, text: String) -> Self {
+		Self { version, text }
+	}
+
+	/// Applies the changes sent by the editor, in order. A change without range replaces the
+	/// whole content.
+	///
+	/// Returns an error when a range doesn't match the content, which means that the document
+	/// is out of sync with the editor
+	pub(crate) fn apply_changes(
+		&mut self,
+		version: i32,
+		changes: Vec<TextDocumentContentChangeEvent>,
+	) -> Result<(), String> {
+		for change in changes {
+			match change.range {
+				Some(range) => {
+					let span = self.byte_span(&range)?;
+					self.text.replace_range(span, &change.text);
+				}
+				None => self.text = change.text,
+			}
+		}
+		self.version = version;
+		Ok(())
+	}
+
+	/// Converts a range of the editor, in lines and UTF-16 characters, to a range of bytes
+	pub(crate) fn byte_span(&self, range: &Range) -> Result<std::ops::Range<usize>, String> {
+		let file = self.as_file();
+		range_to_byte_span(&file, 0, range).map_err(|error| error.to_string())
+	}
+
+	/// Computes the edits that transform the content of the document into `formatted`. When
+	/// `range` is set, only the edits that touch the lines of the range are returned.
+	///
+	/// The edits replace whole lines, the lines that are unchanged aren't part of any edit
+	pub(crate) fn edits_to(&self, formatted: &str, range: Option<&Range>) -> Vec<TextEdit> {
+		let file = self.as_file();
+		let mut edits = Vec::new();
+		// the byte offset of the current line of the content of the document
+		let mut offset = 0;
+		let mut lines = diff_lines(&self.text, formatted).into_iter().peekable();
+
+		while let Some(line) = lines.next() {
+			if line.change == LineChange::Equal {
+				offset += line.text.len();
+				continue;
+			}
+
+			// groups the consecutive changed lines in a single edit
+			let start = offset;
+			let mut new_text = String::new();
+			let mut line = Some(line);
+			while let Some(changed) = line {
+				match changed.change {
+					LineChange::Delete => offset += changed.text.len(),
+					LineChange::Insert => new_text.push_str(changed.text),
+					LineChange::Equal => unreachable!(),
+				}
+				line = lines.next_if(|next| next.change != LineChange::Equal);
+			}
+
+			let edit_range = match byte_span_to_range(&file, 0, start..offset) {
+				Ok(edit_range) => edit_range,
+				// the span comes from the content itself
+				Err(_) => continue,
+			};
+			// an edit that ends at the start of a line doesn't touch that line
+			let last_line = if edit_range.end.character == 0 && edit_range.end > edit_range.start {
+				edit_range.end.line - 1
+			} else {
+				edit_range.end.line
+			};
+			let is_selected = range.map_or(true, |range| {
+				edit_range.start.line <= range.end.line && range.start.line <= last_line
+			});
+			if is_selected {
+				edits.push(TextEdit {
+					range: edit_range,
+					new_text,
+				});
+			}
+		}
+
+		edits
+	}
+
+	/// Encodes the tokens, sorted by position, as expected by the editor: the position of each
+	/// token is relative to the previous one, in lines and UTF-16 characters.
+	///
+	/// The tokens that span multiple lines, like block comments, are split in one token per line
+	pub(crate) fn encode_tokens(&self, tokens: &[ClassifiedToken]) -> Vec<SemanticToken> {
+		let mut encoded = Vec::new();
+		// the line of the last token, and the byte offset where that line starts
+		let mut line = 0;
+		let mut line_start = 0;
+		let mut previous_line = 0;
+		let mut previous_character = 0;
+
+		for token in tokens {
+			let token_type = SemanticTokenType::ALL
+				.iter()
+				.position(|token_type| *token_type == token.token_type)
+				.unwrap_or_default() as u32;
+			let mut piece_start = usize::from(token.range.start());
+
+			for piece in self.text[token.range].split('\n') {
+				let before = &self.text[line_start..piece_start];
+				if let Some(last_newline) = before.rfind('\n') {
+					line += before.matches('\n').count() as u32;
+					line_start += last_newline + 1;
+				}
+				let start = piece_start;
+				piece_start += piece.len() + 1;
+
+				let piece = piece.strip_suffix('\r').unwrap_or(piece);
+				if piece.is_empty() {
+					continue;
+				}
+				let character = utf16_len(&self.text[line_start..start]);
+				let delta_start = if line == previous_line {
+					character - previous_character
+				} else {
+					character
+				};
+				encoded.push(SemanticToken {
+					delta_line: line - previous_line,
+					delta_start,
+					length: utf16_len(piece),
+					token_type,
+					token_modifiers_bitset: token.modifiers.bits(),
+				});
+				previous_line = line;
+				previous_character = character;
+			}
+		}
+
+		encoded
+	}
+
+	/// The content of the document, in the form used by the diagnostics
+	pub(crate) fn as_file(&self) -> SimpleFile {
+		SimpleFile::new(String::new(), self.text.clone())
+	}
+}
+
+fn utf16_len(text: &str) -> u32 {
+	text.encode_utf16().count() as u32
+}
+
+#[cfg(test)]
+mod test {
+	use super::Document;
+	use lsp_types::{Position, Range, SemanticToken, TextDocumentContentChangeEvent};
+	use rslint_parser::parse_text;
+	use rslint_parser::semantic_tokens::semantic_tokens;
+
+	fn range(start: (u32, u32), end: (u32, u32)) -> Range {
+		Range::new(Position::new(start.0, start.1), Position::new(end.0, end.1))
+	}
+
+	#[test]
+	fn applies_changes() {
+		let mut document = Document::new(1, "let a = 1;\nlet é = 2;\n".to_string());
+		document
+			.apply_changes(
+				2,
+				vec![
+					TextDocumentContentChangeEvent {
+						range: Some(range((1, 4), (1, 5))),
+						range_length: None,
+						text: "b".to_string(),
+					},
+					TextDocumentContentChangeEvent {
+						range: Some(range((2, 0), (2, 0))),
+						range_length: None,
+						text: "c;".to_string(),
+					},
+				],
+			)
+			.unwrap();
+
+		assert_eq!(document.version, 2);
+		assert_eq!(document.text, "let a = 1;\nlet b = 2;\nc;");
+	}
+
+	#[test]
+	fn computes_edits() {
+		let document = Document::new(1, "let a  = 1\nlet b = 2;\nlet c  = 3\n".to_string());
+		let formatted = "let a = 1;\nlet b = 2;\nlet c = 3;\n";
+
+		let edits = document.edits_to(formatted, None);
+		assert_eq!(edits.len(), 2);
+		assert_eq!(edits[0].range, range((0, 0), (1, 0)));
+		assert_eq!(edits[0].new_text, "let a = 1;\n");
+		assert_eq!(edits[1].range, range((2, 0), (3, 0)));
+
+		let edits = document.edits_to(formatted, Some(&range((2, 2), (2, 4))));
+		assert_eq!(edits.len(), 1);
+		assert_eq!(edits[0].new_text, "let c = 3;\n");
+	}
+
+	#[test]
+	fn encodes_tokens() {
+		let document = Document::new(1, "let a = \"é\"; /* a\r\n  b */ a;".to_string());
+		let parse = parse_text(&document.text, 0);
+		let tokens = semantic_tokens(&parse.syntax(), None);
+
+		let encoded: Vec<_> = document
+			.encode_tokens(&tokens)
+			.into_iter()
+			.map(|token| {
+				let SemanticToken {
+					delta_line,
+					delta_start,
+					length,
+					..
+				} = token;
+				(delta_line, delta_start, length)
+			})
+			.collect();
+		assert_eq!(
+			encoded,
+			// `let`, `a`, the string, the two lines of the comment, and `a`
+			vec![
+				(0, 0, 3),
+				(0, 4, 1),
+				(0, 4, 3),
+				(0, 5, 4),
+				(1, 0, 6),
+				(0, 7, 1)
+			]
+		);
+	}
+}
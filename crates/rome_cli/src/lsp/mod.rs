@@ -0,0 +1,427 @@
+//! A language server, that editors run to show the diagnostics of the parser and to format the
+//! documents while they are edited, without saving them.
+//!
+//! The server speaks the [language server protocol](https://microsoft.github.io/language-server-protocol/)
+//! on stdin and stdout, and handles one message at a time. The options of the formatter are
+//! resolved like the `format` command does, the options sent by the editor are only used when
+//! neither the configuration file nor the EditorConfig files set them.
+mod document;
+mod transport;
+
+use crate::commands::format::FormatSettings;
+use crate::configuration::{load_configuration, ConfigurationError, FormatterConfiguration};
+use crate::editorconfig::EditorConfig;
+use document::Document;
+use lsp_types::notification::{
+	DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Exit, Notification,
+	PublishDiagnostics, ShowMessage,
+};
+use lsp_types::request::{
+	Formatting, Initialize, RangeFormatting, Request, SemanticTokensFullRequest,
+	SemanticTokensRangeRequest, Shutdown,
+};
+use lsp_types::{
+	DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+	DocumentFormattingParams, DocumentRangeFormattingParams, FormattingOptions, InitializeParams,
+	InitializeResult, MessageType, OneOf, PublishDiagnosticsParams, Range, SemanticTokenModifier,
+	SemanticTokens, SemanticTokensFullOptions, SemanticTokensLegend, SemanticTokensOptions,
+	SemanticTokensParams, SemanticTokensRangeParams, ServerCapabilities, ServerInfo,
+	ShowMessageParams, TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncOptions,
+	Url,
+};
+use rome_core::{create_app, App};
+use rome_formatter::IndentStyle;
+use rome_path::{format_source, RomePath};
+use rslint_errors::lsp::convert_to_lsp_diagnostic;
+use rslint_errors::Diagnostic;
+use rslint_parser::semantic_tokens::{semantic_tokens, SemanticTokenModifiers, SemanticTokenType};
+use rslint_parser::{TextRange, TextSize};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::env;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use transport::{error_codes, read_message, write_error, write_notification, write_response};
+
+/// The lifecycle of the server, as driven by the client
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum State {
+	/// The client hasn't sent the `initialize` request yet
+	Uninitialized,
+	Running,
+	/// The client has sent the `shutdown` request, and is about to send the `exit` notification
+	ShutDown,
+}
+
+/// An error sent back as response to a request
+type RequestError = (i64, String);
+
+struct Server<W> {
+	writer: W,
+	state: State,
+	app: App,
+	settings: FormatSettings,
+	documents: HashMap<Url, Document>,
+}
+
+/// Runs the server until the client sends the `exit` notification or closes the stream.
+///
+/// Returns `true` if the client has asked the server to shut down before exiting
+pub(crate) fn run_server(mut reader: impl BufRead, writer: impl Write) -> io::Result<bool> {
+	let mut server = Server {
+		writer,
+		state: State::Uninitialized,
+		app: create_app(),
+		settings: FormatSettings::default(),
+		documents: HashMap::new(),
+	};
+
+	loop {
+		let message = match read_message(&mut reader) {
+			Ok(Some(message)) => message,
+			Ok(None) => break,
+			Err(error) if error.kind() == io::ErrorKind::InvalidData => {
+				write_error(
+					&mut server.writer,
+					&Value::Null,
+					error_codes::PARSE_ERROR,
+					error.to_string(),
+				)?;
+				continue;
+			}
+			Err(error) => return Err(error),
+		};
+
+		match (message.id, message.method) {
+			(Some(id), Some(method)) => match server.handle_request(&method, message.params) {
+				Ok(result) => write_response(&mut server.writer, &id, result)?,
+				Err((code, error)) => write_error(&mut server.writer, &id, code, error)?,
+			},
+			(None, Some(method)) if method == Exit::METHOD => break,
+			(None, Some(method)) => server.handle_notification(&method, message.params)?,
+			// the server doesn't send requests, so it doesn't expect responses
+			(_, None) => {}
+		}
+	}
+
+	Ok(server.state == State::ShutDown)
+}
+
+impl<W: Write> Server<W> {
+	fn handle_request(&mut self, method: &str, params: Value) -> Result<Value, RequestError> {
+		match (self.state, method) {
+			(State::Uninitialized, Initialize::METHOD) => {
+				let result = self.initialize(parse_params(params)?);
+				self.state = State::Running;
+				Ok(to_value(result))
+			}
+			(State::Uninitialized, _) => Err((
+				error_codes::SERVER_NOT_INITIALIZED,
+				"The server hasn't been initialized".to_string(),
+			)),
+			(State::ShutDown, _) => Err((
+				error_codes::INVALID_REQUEST,
+				"The server is shutting down".to_string(),
+			)),
+			(State::Running, Shutdown::METHOD) => {
+				self.state = State::ShutDown;
+				Ok(Value::Null)
+			}
+			(State::Running, Formatting::METHOD) => {
+				let params: DocumentFormattingParams = parse_params(params)?;
+				self.format(&params.text_document.uri, &params.options, None)
+			}
+			(State::Running, RangeFormatting::METHOD) => {
+				let params: DocumentRangeFormattingParams = parse_params(params)?;
+				self.format(
+					&params.text_document.uri,
+					&params.options,
+					Some(&params.range),
+				)
+			}
+			(State::Running, SemanticTokensFullRequest::METHOD) => {
+				let params: SemanticTokensParams = parse_params(params)?;
+				self.semantic_tokens(&params.text_document.uri, None)
+			}
+			(State::Running, SemanticTokensRangeRequest::METHOD) => {
+				let params: SemanticTokensRangeParams = parse_params(params)?;
+				self.semantic_tokens(&params.text_document.uri, Some(&params.range))
+			}
+			(State::Running, _) => Err((
+				error_codes::METHOD_NOT_FOUND,
+				format!("The method \"{}\" isn't supported", method),
+			)),
+		}
+	}
+
+	/// The notifications received before the initialization, or that can't be understood,
+	/// are dropped as required by the protocol
+	fn handle_notification(&mut self, method: &str, params: Value) -> io::Result<()> {
+		if self.state != State::Running {
+			return Ok(());
+		}
+
+		match method {
+			DidOpenTextDocument::METHOD => {
+				if let Ok(params) = parse_params::<DidOpenTextDocumentParams>(params) {
+					let document = params.text_document;
+					self.documents.insert(
+						document.uri.clone(),
+						Document::new(document.version, document.text),
+					);
+					self.publish_diagnostics(&document.uri)?;
+				}
+			}
+			DidChangeTextDocument::METHOD => {
+				if let Ok(params) = parse_params::<DidChangeTextDocumentParams>(params) {
+					let uri = params.text_document.uri;
+					let result = match self.documents.get_mut(&uri) {
+						Some(document) => document
+							.apply_changes(params.text_document.version, params.content_changes),
+						None => return Ok(()),
+					};
+					match result {
+						Ok(()) => self.publish_diagnostics(&uri)?,
+						Err(error) => {
+							// the content is unknown from now on, the editor has to open it again
+							self.documents.remove(&uri);
+							self.show_message(
+								MessageType::Error,
+								format!("The document \"{}\" is out of sync: {}", uri, error),
+							)?;
+						}
+					}
+				}
+			}
+			DidCloseTextDocument::METHOD => {
+				if let Ok(params) = parse_params::<DidCloseTextDocumentParams>(params) {
+					let uri = params.text_document.uri;
+					self.documents.remove(&uri);
+					// the diagnostics of a closed document are cleared
+					self.notify::<PublishDiagnostics>(PublishDiagnosticsParams::new(
+						uri,
+						Vec::new(),
+						None,
+					))?;
+				}
+			}
+			// `initialized`, `$/cancelRequest` and the unknown notifications
+			_ => {}
+		}
+
+		Ok(())
+	}
+
+	/// Loads the configuration of the project opened in the editor
+	fn initialize(&mut self, params: InitializeParams) -> InitializeResult {
+		let root = params
+			.root_uri
+			.and_then(|uri| uri.to_file_path().ok())
+			.or_else(|| env::current_dir().ok());
+		let configuration = match root.map(|root| load_configuration(&root)) {
+			Some(Ok(loaded)) => loaded.map(|loaded| loaded.configuration),
+			Some(Err(error)) => {
+				// the diagnostics are printed on stderr, that editors usually log
+				error.emit();
+				let path = match &error {
+					ConfigurationError::Io { path, .. }
+					| ConfigurationError::Invalid { path, .. } => path,
+				};
+				let message = format!(
+					"The configuration file \"{}\" is invalid, the default options are used",
+					path.display()
+				);
+				// the client is still listening, there's no other way to report the failure
+				self.show_message(MessageType::Warning, message).ok();
+				None
+			}
+			None => None,
+		};
+		self.settings = FormatSettings {
+			editorconfig: EditorConfig::default(),
+			configuration: configuration.unwrap_or_default(),
+			arguments: FormatterConfiguration::default(),
+		};
+
+		InitializeResult {
+			capabilities: ServerCapabilities {
+				text_document_sync: Some(TextDocumentSyncCapability::Options(
+					TextDocumentSyncOptions {
+						open_close: Some(true),
+						change: Some(TextDocumentSyncKind::Incremental),
+						..TextDocumentSyncOptions::default()
+					},
+				)),
+				document_formatting_provider: Some(OneOf::Left(true)),
+				document_range_formatting_provider: Some(OneOf::Left(true)),
+				semantic_tokens_provider: Some(
+					SemanticTokensOptions {
+						legend: SemanticTokensLegend {
+							token_types: SemanticTokenType::ALL
+								.iter()
+								.map(|token_type| token_type.as_str().into())
+								.collect(),
+							token_modifiers: SemanticTokenModifiers::ALL
+								.iter()
+								.map(|&modifier| SemanticTokenModifier::new(modifier))
+								.collect(),
+						},
+						range: Some(true),
+						full: Some(SemanticTokensFullOptions::Bool(true)),
+						..SemanticTokensOptions::default()
+					}
+					.into(),
+				),
+				..ServerCapabilities::default()
+			},
+			server_info: Some(ServerInfo {
+				name: "rome".to_string(),
+				version: Some(env!("CARGO_PKG_VERSION").to_string()),
+			}),
+		}
+	}
+
+	/// Formats the document, returning the edits to apply or `null` when the document can't
+	/// be formatted. The documents that have syntax errors aren't formatted, as the code
+	/// that is being typed would be rewritten
+	fn format(
+		&self,
+		uri: &Url,
+		editor_options: &FormattingOptions,
+		range: Option<&Range>,
+	) -> Result<Value, RequestError> {
+		let document = self.documents.get(uri).ok_or_else(|| {
+			(
+				error_codes::INVALID_PARAMS,
+				format!("The document \"{}\" isn't open", uri),
+			)
+		})?;
+		let rome_path = RomePath::new(document_path(uri))
+			.deduce_handler_with_content(&self.app, &document.text);
+		if !parse_diagnostics(&rome_path, &document.text).is_empty() {
+			return Ok(Value::Null);
+		}
+
+		let mut formatter = editor_formatter(editor_options);
+		formatter.merge(&self.settings.resolve(&rome_path));
+		match format_source(&rome_path, &document.text, formatter.to_format_options()) {
+			Ok(formatted) => Ok(to_value(document.edits_to(formatted.code(), range))),
+			Err(_) => Ok(Value::Null),
+		}
+	}
+
+	/// Classifies the tokens of the document, or of the lines of `range`. The response is `null`
+	/// for the documents of the languages that don't have a parser
+	fn semantic_tokens(&self, uri: &Url, range: Option<&Range>) -> Result<Value, RequestError> {
+		let document = self.documents.get(uri).ok_or_else(|| {
+			(
+				error_codes::INVALID_PARAMS,
+				format!("The document \"{}\" isn't open", uri),
+			)
+		})?;
+		let rome_path = RomePath::new(document_path(uri))
+			.deduce_handler_with_content(&self.app, &document.text);
+		let parse = match rome_path
+			.get_handler()
+			.and_then(|handler| handler.parse(&document.text, 0))
+		{
+			Some(parse) => parse,
+			None => return Ok(Value::Null),
+		};
+
+		let range = match range {
+			Some(range) => {
+				let span = document
+					.byte_span(range)
+					.map_err(|error| (error_codes::INVALID_PARAMS, error))?;
+				Some(TextRange::new(
+					TextSize::from(span.start as u32),
+					TextSize::from(span.end as u32),
+				))
+			}
+			None => None,
+		};
+		let tokens = semantic_tokens(&parse.root, range);
+
+		Ok(to_value(SemanticTokens {
+			result_id: None,
+			data: document.encode_tokens(&tokens),
+		}))
+	}
+
+	fn publish_diagnostics(&mut self, uri: &Url) -> io::Result<()> {
+		let document = match self.documents.get(uri) {
+			Some(document) => document,
+			None => return Ok(()),
+		};
+		let rome_path = RomePath::new(document_path(uri))
+			.deduce_handler_with_content(&self.app, &document.text);
+		let file = document.as_file();
+		let diagnostics = parse_diagnostics(&rome_path, &document.text)
+			.into_iter()
+			.filter_map(|diagnostic| {
+				convert_to_lsp_diagnostic(
+					diagnostic,
+					&file,
+					0,
+					uri.clone(),
+					Some("rome".to_string()),
+				)
+			})
+			.collect();
+
+		let params =
+			PublishDiagnosticsParams::new(uri.clone(), diagnostics, Some(document.version));
+		self.notify::<PublishDiagnostics>(params)
+	}
+
+	fn show_message(&mut self, typ: MessageType, message: String) -> io::Result<()> {
+		self.notify::<ShowMessage>(ShowMessageParams { typ, message })
+	}
+
+	fn notify<N: Notification>(&mut self, params: N::Params) -> io::Result<()> {
+		write_notification(&mut self.writer, N::METHOD, to_value(params))
+	}
+}
+
+/// The diagnostics of the parser, the documents of the languages that don't have a parser, like
+/// JSON, don't have diagnostics
+fn parse_diagnostics(rome_path: &RomePath, text: &str) -> Vec<Diagnostic> {
+	rome_path
+		.get_handler()
+		.and_then(|handler| handler.parse(text, 0))
+		.map(|parse| parse.diagnostics)
+		.unwrap_or_default()
+}
+
+/// The options of the formatter sent by the editor
+fn editor_formatter(options: &FormattingOptions) -> FormatterConfiguration {
+	let tab_size = u8::try_from(options.tab_size).ok();
+	FormatterConfiguration {
+		indent_style: Some(if options.insert_spaces {
+			IndentStyle::Space(tab_size.unwrap_or(2))
+		} else {
+			IndentStyle::Tab
+		}),
+		indent_size: tab_size,
+		tab_width: tab_size,
+		..FormatterConfiguration::default()
+	}
+}
+
+/// The path of the document, used to find its language and its options. The documents that
+/// haven't been saved yet don't have a path on the disk
+fn document_path(uri: &Url) -> PathBuf {
+	uri.to_file_path()
+		.unwrap_or_else(|_| PathBuf::from(uri.path()))
+}
+
+fn parse_params<P: DeserializeOwned>(params: Value) -> Result<P, RequestError> {
+	serde_json::from_value(params).map_err(|error| (error_codes::INVALID_PARAMS, error.to_string()))
+}
+
+fn to_value(value: impl serde::Serialize) -> Value {
+	serde_json::to_value(value).expect("the types of the protocol can be serialized")
+}
@@ -0,0 +1,161 @@
+//! Reads and writes the messages of the JSON-RPC protocol, as framed by the language server
+//! protocol: each message is preceded by a `Content-Length` header, followed by an empty line.
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::{self, BufRead, Write};
+
+/// The error codes defined by JSON-RPC and by the language server protocol
+pub(crate) mod error_codes {
+	pub(crate) const PARSE_ERROR: i64 = -32700;
+	pub(crate) const INVALID_REQUEST: i64 = -32600;
+	pub(crate) const METHOD_NOT_FOUND: i64 = -32601;
+	pub(crate) const INVALID_PARAMS: i64 = -32602;
+	pub(crate) const SERVER_NOT_INITIALIZED: i64 = -32002;
+}
+
+/// A message received from the client. A message without `id` is a notification, and a
+/// message without `method` is the response to a request of the server
+#[derive(Debug, Deserialize)]
+pub(crate) struct Message {
+	#[serde(default)]
+	pub(crate) id: Option<Value>,
+	#[serde(default)]
+	pub(crate) method: Option<String>,
+	#[serde(default)]
+	pub(crate) params: Value,
+}
+
+#[derive(Debug, Serialize)]
+struct Response<'a> {
+	jsonrpc: &'static str,
+	id: &'a Value,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	result: Option<Value>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	error: Option<ResponseError>,
+}
+
+#[derive(Debug, Serialize)]
+struct ResponseError {
+	code: i64,
+	message: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Notification<'a> {
+	jsonrpc: &'static str,
+	method: &'a str,
+	params: Value,
+}
+
+/// Reads the next message, returning [None] when the client has closed the stream.
+///
+/// A message that isn't valid JSON is returned as an error of kind [io::ErrorKind::InvalidData],
+/// the stream can still be read after it
+pub(crate) fn read_message(reader: &mut impl BufRead) -> io::Result<Option<Message>> {
+	let mut content_length = None;
+	let mut line = String::new();
+	loop {
+		line.clear();
+		if reader.read_line(&mut line)? == 0 {
+			return Ok(None);
+		}
+		let header = line.trim_end();
+		if header.is_empty() {
+			break;
+		}
+		// the other headers, like `Content-Type`, are ignored
+		if let Some((name, value)) = header.split_once(':') {
+			if name.trim().eq_ignore_ascii_case("content-length") {
+				content_length = value.trim().parse::<usize>().ok();
+			}
+		}
+	}
+
+	let content_length = content_length.ok_or_else(|| {
+		io::Error::new(
+			io::ErrorKind::InvalidData,
+			"the message doesn't have a valid Content-Length header",
+		)
+	})?;
+	let mut content = vec![0; content_length];
+	reader.read_exact(&mut content)?;
+
+	serde_json::from_slice(&content)
+		.map(Some)
+		.map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+}
+
+pub(crate) fn write_response(writer: &mut impl Write, id: &Value, result: Value) -> io::Result<()> {
+	write_message(
+		writer,
+		&Response {
+			jsonrpc: "2.0",
+			id,
+			result: Some(result),
+			error: None,
+		},
+	)
+}
+
+pub(crate) fn write_error(
+	writer: &mut impl Write,
+	id: &Value,
+	code: i64,
+	message: String,
+) -> io::Result<()> {
+	write_message(
+		writer,
+		&Response {
+			jsonrpc: "2.0",
+			id,
+			result: None,
+			error: Some(ResponseError { code, message }),
+		},
+	)
+}
+
+pub(crate) fn write_notification(
+	writer: &mut impl Write,
+	method: &str,
+	params: Value,
+) -> io::Result<()> {
+	write_message(
+		writer,
+		&Notification {
+			jsonrpc: "2.0",
+			method,
+			params,
+		},
+	)
+}
+
+fn write_message(writer: &mut impl Write, message: &impl Serialize) -> io::Result<()> {
+	let content = serde_json::to_vec(message)?;
+	write!(writer, "Content-Length: {}\r\n\r\n", content.len())?;
+	writer.write_all(&content)?;
+	writer.flush()
+}
+
+#[cfg(test)]
+mod test {
+	use super::{read_message, write_notification};
+	use serde_json::json;
+	use std::io::Cursor;
+
+	#[test]
+	fn reads_written_messages() {
+		let mut output = Vec::new();
+		write_notification(&mut output, "initialized", json!({})).unwrap();
+		write_notification(&mut output, "exit", json!(null)).unwrap();
+		assert!(output.starts_with(b"Content-Length: 52\r\n\r\n{\"jsonrpc\""));
+
+		let mut input = Cursor::new(output);
+		let message = read_message(&mut input).unwrap().unwrap();
+		assert_eq!(message.method.as_deref(), Some("initialized"));
+		assert_eq!(message.id, None);
+		let message = read_message(&mut input).unwrap().unwrap();
+		assert_eq!(message.method.as_deref(), Some("exit"));
+		assert!(read_message(&mut input).unwrap().is_none());
+	}
+}
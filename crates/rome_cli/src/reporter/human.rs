@@ -0,0 +1,116 @@
+use crate::diff::print_diff;
+use crate::reporter::{Mode, Reporter, Status, Summary};
+use rslint_errors::file::Files;
+use rslint_errors::termcolor::{ColorChoice, StandardStream};
+use rslint_errors::{Diagnostic, Emitter};
+use std::path::Path;
+use std::time::Duration;
+
+/// Prints the diagnostics on stdout, and the skipped and ignored files on stderr
+pub(crate) struct HumanReporter {
+	stdout: StandardStream,
+	mode: Mode,
+	/// Whether the skipped and ignored files are printed
+	is_verbose: bool,
+	/// Whether the files that have been formatted or that are unchanged are printed
+	list_files: bool,
+}
+
+impl HumanReporter {
+	pub(crate) fn new(mode: Mode, is_verbose: bool, list_files: bool) -> Self {
+		let color = if atty::is(atty::Stream::Stdout) {
+			ColorChoice::Auto
+		} else {
+			ColorChoice::Never
+		};
+
+		Self {
+			stdout: StandardStream::stdout(color),
+			mode,
+			is_verbose,
+			list_files,
+		}
+	}
+}
+
+// failing to print on stdout isn't something we can recover from
+impl Reporter for HumanReporter {
+	fn report_file(&mut self, path: &Path, status: Status, duration: Duration) {
+		match status {
+			Status::Formatted | Status::Unchanged if self.list_files => println!(
+				"{} \"{}\" in {}ms",
+				if status == Status::Formatted {
+					"Formatted"
+				} else {
+					"Unchanged"
+				},
+				path.display(),
+				duration.as_millis()
+			),
+			Status::Skipped if self.is_verbose => eprintln!(
+				"Skipped \"{}\", the file can't be {}",
+				path.display(),
+				if self.mode == Mode::Lint {
+					"linted"
+				} else {
+					"formatted"
+				}
+			),
+			_ => {}
+		}
+	}
+
+	fn report_diagnostic(&mut self, files: &dyn Files, diagnostic: &Diagnostic) {
+		Emitter::new(files)
+			.emit_with_writer(diagnostic, &mut self.stdout.lock())
+			.expect("cannot print the diagnostic");
+	}
+
+	fn report_not_formatted(&mut self, files: &dyn Files, diagnostic: &Diagnostic, diff: &str) {
+		let mut stdout = self.stdout.lock();
+		Emitter::new(files)
+			.emit_with_writer(diagnostic, &mut stdout)
+			.expect("cannot print the diagnostic");
+		print_diff(&mut stdout, diff).expect("cannot print the diff");
+	}
+
+	fn report_ignored(&mut self, path: &Path) {
+		if self.is_verbose {
+			eprintln!("Ignored \"{}\"", path.display());
+		}
+	}
+
+	fn report_summary(&mut self, summary: &Summary) {
+		match self.mode {
+			Mode::Format => println!(
+				"Processed {} files: {} formatted, {} unchanged, {} skipped, {} failed",
+				summary.total(),
+				summary.formatted,
+				summary.unchanged,
+				summary.skipped,
+				summary.failed
+			),
+			Mode::Check => println!(
+				"Checked {} files: {} not formatted, {} formatted, {} skipped, {} failed",
+				summary.total(),
+				summary.formatted,
+				summary.unchanged,
+				summary.skipped,
+				summary.failed
+			),
+			Mode::Lint => {
+				if summary.fixes > 0 {
+					println!("Applied {} fixes", summary.fixes);
+				}
+				println!(
+					"Linted {} files: {} errors, {} warnings, {} skipped, {} failed",
+					summary.linted,
+					summary.errors,
+					summary.warnings,
+					summary.skipped,
+					summary.failed
+				);
+			}
+		}
+	}
+}
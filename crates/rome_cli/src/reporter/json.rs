@@ -0,0 +1,225 @@
+use crate::reporter::{Mode, Reporter, Status, Summary};
+use rslint_errors::file::{FileId, Files};
+use rslint_errors::Diagnostic;
+use serde::Serialize;
+use std::io::Write;
+use std::path::Path;
+use std::time::Duration;
+
+/// Streams the results as [newline delimited JSON](http://ndjson.org), one record per line.
+///
+/// Every record has a `type` field: `file`, `diagnostic`, `ignored` or `summary`
+pub(crate) struct JsonReporter<W> {
+	writer: W,
+	mode: Mode,
+}
+
+impl<W: Write> JsonReporter<W> {
+	pub(crate) fn new(writer: W, mode: Mode) -> Self {
+		Self { writer, mode }
+	}
+
+	fn write_record(&mut self, record: &Record) {
+		// failing to print on stdout isn't something we can recover from
+		serde_json::to_writer(&mut self.writer, record)
+			.map_err(std::io::Error::from)
+			.and_then(|_| writeln!(self.writer))
+			.and_then(|_| self.writer.flush())
+			.expect("cannot print the record");
+	}
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Record<'a> {
+	File {
+		path: String,
+		status: &'static str,
+		#[serde(skip_serializing_if = "Option::is_none")]
+		error: Option<&'a str>,
+		duration_ms: f64,
+	},
+	Diagnostic {
+		file: &'a str,
+		/// The line and column range of the primary label, if the diagnostic has one
+		location: Option<Location>,
+		#[serde(flatten)]
+		diagnostic: &'a Diagnostic,
+	},
+	Ignored {
+		path: String,
+	},
+	/// The counters of the other commands are omitted
+	Summary {
+		mode: &'static str,
+		total: usize,
+		#[serde(skip_serializing_if = "Option::is_none")]
+		formatted: Option<usize>,
+		#[serde(skip_serializing_if = "Option::is_none")]
+		unchanged: Option<usize>,
+		#[serde(skip_serializing_if = "Option::is_none")]
+		linted: Option<usize>,
+		#[serde(skip_serializing_if = "Option::is_none")]
+		errors: Option<usize>,
+		#[serde(skip_serializing_if = "Option::is_none")]
+		warnings: Option<usize>,
+		#[serde(skip_serializing_if = "Option::is_none")]
+		fixes: Option<usize>,
+		skipped: usize,
+		failed: usize,
+	},
+}
+
+#[derive(Serialize)]
+struct Location {
+	start: Position,
+	end: Position,
+}
+
+/// Both the line and the column start from 1, the column is counted in bytes
+#[derive(Serialize)]
+struct Position {
+	line: usize,
+	column: usize,
+}
+
+impl Position {
+	fn new(files: &dyn Files, file_id: FileId, byte_index: usize) -> Option<Self> {
+		let line_index = files.line_index(file_id, byte_index)?;
+		let line_start = files.line_range(file_id, line_index)?.start;
+		Some(Self {
+			line: line_index + 1,
+			column: byte_index.saturating_sub(line_start) + 1,
+		})
+	}
+}
+
+impl<W: Write> Reporter for JsonReporter<W> {
+	fn report_file(&mut self, path: &Path, status: Status, duration: Duration) {
+		let (status, error) = match status {
+			Status::Formatted => ("formatted", None),
+			Status::Unchanged => ("unchanged", None),
+			Status::Linted => ("linted", None),
+			Status::Skipped => ("skipped", None),
+			Status::NotFormatted => ("not_formatted", None),
+			Status::Failed(error) => ("error", Some(error)),
+		};
+
+		self.write_record(&Record::File {
+			path: path.display().to_string(),
+			status,
+			error,
+			duration_ms: duration.as_secs_f64() * 1000.0,
+		});
+	}
+
+	fn report_diagnostic(&mut self, files: &dyn Files, diagnostic: &Diagnostic) {
+		let location = diagnostic.primary.as_ref().and_then(|primary| {
+			let span = &primary.span;
+			Some(Location {
+				start: Position::new(files, span.file, span.range.start)?,
+				end: Position::new(files, span.file, span.range.end)?,
+			})
+		});
+
+		self.write_record(&Record::Diagnostic {
+			file: files.name(diagnostic.file_id).unwrap_or_default(),
+			location,
+			diagnostic,
+		});
+	}
+
+	fn report_not_formatted(&mut self, files: &dyn Files, diagnostic: &Diagnostic, diff: &str) {
+		// the diff is kept as plain text in a note of the diagnostic
+		let diagnostic = diagnostic.clone().footer_note(diff);
+		self.report_diagnostic(files, &diagnostic);
+	}
+
+	fn report_ignored(&mut self, path: &Path) {
+		self.write_record(&Record::Ignored {
+			path: path.display().to_string(),
+		});
+	}
+
+	fn report_summary(&mut self, summary: &Summary) {
+		let is_lint = self.mode == Mode::Lint;
+		let format_counter = |counter| if is_lint { None } else { Some(counter) };
+		let lint_counter = |counter| if is_lint { Some(counter) } else { None };
+
+		self.write_record(&Record::Summary {
+			mode: match self.mode {
+				Mode::Format => "format",
+				Mode::Check => "check",
+				Mode::Lint => "lint",
+			},
+			total: summary.total(),
+			formatted: format_counter(summary.formatted),
+			unchanged: format_counter(summary.unchanged),
+			linted: lint_counter(summary.linted),
+			errors: lint_counter(summary.errors),
+			warnings: lint_counter(summary.warnings),
+			fixes: lint_counter(summary.fixes),
+			skipped: summary.skipped,
+			failed: summary.failed,
+		});
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::JsonReporter;
+	use crate::reporter::{Mode, Reporter, Status, Summary};
+	use rome_core::Workspace;
+	use rslint_errors::Diagnostic;
+	use std::path::Path;
+	use std::time::Duration;
+
+	#[test]
+	fn streams_records() {
+		let mut output = Vec::new();
+		let mut reporter = JsonReporter::new(&mut output, Mode::Lint);
+
+		let mut workspace = Workspace::new();
+		workspace.insert(Path::new("rome.json"), "{}");
+		let file_id = workspace.insert(Path::new("index.js"), "let a;\nlet b\n");
+		let diagnostic =
+			Diagnostic::error(file_id, "format", "Something is wrong").primary(12usize..13, "here");
+		reporter.report_diagnostic(&workspace, &diagnostic);
+		reporter.report_file(
+			Path::new("index.js"),
+			Status::Failed("cannot parse"),
+			Duration::from_millis(2),
+		);
+		reporter.report_summary(&Summary {
+			linted: 2,
+			errors: 1,
+			failed: 1,
+			..Summary::default()
+		});
+
+		let output = String::from_utf8(output).unwrap();
+		let records: Vec<serde_json::Value> = output
+			.lines()
+			.map(|line| serde_json::from_str(line).unwrap())
+			.collect();
+
+		assert_eq!(records.len(), 3);
+		assert_eq!(records[0]["type"], "diagnostic");
+		assert_eq!(records[0]["file"], "index.js");
+		assert_eq!(records[0]["code"], "format");
+		assert_eq!(records[0]["title"], "Something is wrong");
+		assert_eq!(records[0]["location"]["start"]["line"], 2);
+		assert_eq!(records[0]["location"]["start"]["column"], 6);
+		assert_eq!(records[0]["primary"]["span"]["range"]["start"], 12);
+		assert_eq!(records[1]["type"], "file");
+		assert_eq!(records[1]["status"], "error");
+		assert_eq!(records[1]["error"], "cannot parse");
+		assert_eq!(records[1]["duration_ms"], 2.0);
+		assert_eq!(records[2]["type"], "summary");
+		assert_eq!(records[2]["mode"], "lint");
+		assert_eq!(records[2]["total"], 3);
+		assert_eq!(records[2]["errors"], 1);
+		// the counters of the formatter are omitted
+		assert!(records[2].get("formatted").is_none());
+	}
+}
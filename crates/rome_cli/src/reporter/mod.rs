@@ -0,0 +1,118 @@
+//! Reports the outcome of the processed files.
+//!
+//! The [HumanReporter] prints messages and diagnostics meant to be read in a terminal, while
+//! the [JsonReporter] streams one JSON record per line, meant to be consumed by other tools.
+use rslint_errors::file::Files;
+use rslint_errors::Diagnostic;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+mod human;
+mod json;
+
+pub(crate) use human::HumanReporter;
+pub(crate) use json::JsonReporter;
+
+/// The command whose results are reported
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum Mode {
+	Format,
+	/// The format command in check mode
+	Check,
+	Lint,
+}
+
+/// Counters of what happened to the processed files
+#[derive(Debug, Default, Eq, PartialEq)]
+pub(crate) struct Summary {
+	/// Files that have been formatted, or that would be formatted in check mode
+	pub(crate) formatted: usize,
+	pub(crate) unchanged: usize,
+	pub(crate) linted: usize,
+	/// The diagnostics of the linted files, by severity
+	pub(crate) errors: usize,
+	pub(crate) warnings: usize,
+	/// The fixes that the linter has applied
+	pub(crate) fixes: usize,
+	pub(crate) skipped: usize,
+	pub(crate) failed: usize,
+}
+
+impl Summary {
+	pub(crate) fn total(&self) -> usize {
+		self.formatted + self.unchanged + self.linted + self.skipped + self.failed
+	}
+}
+
+/// What happened to a processed file
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum Status<'a> {
+	Formatted,
+	Unchanged,
+	Linted,
+	/// The file can't be formatted, or linted
+	Skipped,
+	/// Check mode only, the content of the file differs from the formatted code
+	NotFormatted,
+	/// The file couldn't be processed, along with the reason
+	Failed(&'a str),
+}
+
+pub(crate) trait Reporter {
+	/// Reports the status of a file, along with the time spent processing it
+	fn report_file(&mut self, path: &Path, status: Status, duration: Duration);
+
+	/// Reports a diagnostic, `files` holds the name and the content of the file the diagnostic
+	/// belongs to
+	fn report_diagnostic(&mut self, files: &dyn Files, diagnostic: &Diagnostic);
+
+	/// Reports the diagnostic of a file that isn't formatted, along with the unified diff between
+	/// its content and the formatted code
+	fn report_not_formatted(&mut self, files: &dyn Files, diagnostic: &Diagnostic, diff: &str);
+
+	/// Reports a file or a directory that has been ignored
+	fn report_ignored(&mut self, path: &Path);
+
+	/// Reports the counters, once all the files have been processed
+	fn report_summary(&mut self, summary: &Summary);
+}
+
+/// The kinds of reporter that can be selected with `--reporter`
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum ReporterKind {
+	Human,
+	Json,
+}
+
+impl Default for ReporterKind {
+	fn default() -> Self {
+		Self::Human
+	}
+}
+
+impl FromStr for ReporterKind {
+	type Err = &'static str;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"human" => Ok(Self::Human),
+			"json" => Ok(Self::Json),
+			_ => Err("Value not supported for reporter"),
+		}
+	}
+}
+
+/// Creates the reporter of the results of `mode`, printing on stdout. When `list_files` is `true`,
+/// the human reporter prints a line for each processed file
+pub(crate) fn create_reporter(
+	kind: ReporterKind,
+	mode: Mode,
+	is_verbose: bool,
+	list_files: bool,
+) -> Box<dyn Reporter> {
+	match kind {
+		ReporterKind::Human => Box::new(HumanReporter::new(mode, is_verbose, list_files)),
+		ReporterKind::Json => Box::new(JsonReporter::new(std::io::stdout(), mode)),
+	}
+}
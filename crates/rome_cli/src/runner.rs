@@ -0,0 +1,130 @@
+//! Processes files concurrently on a work-stealing thread pool.
+//!
+//! The processing of a file is independent from the other files, so the files are dispatched
+//! on the threads of the pool, while the results are reported on the main thread, in the same order
+//! of the files.
+//!
+//! The run can be interrupted with Ctrl-C: the files that are being processed are completed, so a file
+//! is never left half-written, while the files that haven't been started yet are skipped.
+use rayon::ThreadPoolBuilder;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Once};
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+static INTERRUPT_HANDLER: Once = Once::new();
+
+/// Replaces the default handler of Ctrl-C, which would kill the process immediately, with one
+/// that signals to the runner to stop processing new files
+pub(crate) fn install_interrupt_handler() {
+	INTERRUPT_HANDLER.call_once(|| {
+		ctrlc::set_handler(|| INTERRUPTED.store(true, Ordering::SeqCst))
+			.expect("cannot set the Ctrl-C handler");
+	});
+}
+
+/// Returns `true` if the user asked to stop the process
+pub(crate) fn is_interrupted() -> bool {
+	INTERRUPTED.load(Ordering::SeqCst)
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum RunStatus {
+	/// All the files have been processed
+	Completed,
+	/// The run has been interrupted, some files haven't been processed
+	Interrupted,
+}
+
+/// Calls `process` on every file, using a pool of `threads` threads. When `threads` is [None], the
+/// pool has one thread for each CPU.
+///
+/// `report` is called on the current thread with the result of each file, following the order of `files`
+pub(crate) fn process_files<R, P, F>(
+	files: &[PathBuf],
+	threads: Option<usize>,
+	process: P,
+	mut report: F,
+) -> RunStatus
+where
+	R: Send,
+	P: Fn(&Path) -> R + Sync,
+	F: FnMut(&Path, R),
+{
+	install_interrupt_handler();
+
+	let mut builder = ThreadPoolBuilder::new();
+	if let Some(threads) = threads {
+		builder = builder.num_threads(threads);
+	}
+	let pool = builder.build().expect("cannot create the thread pool");
+
+	let (sender, receiver) = mpsc::channel();
+	let process = &process;
+
+	pool.in_place_scope(|scope| {
+		for (index, file) in files.iter().enumerate() {
+			let sender = sender.clone();
+			scope.spawn(move |_| {
+				if is_interrupted() {
+					return;
+				}
+				let result = process(file);
+				// the receiver lives until the end of the scope, this can't fail
+				sender.send((index, result)).ok();
+			});
+		}
+		drop(sender);
+
+		// results arrive in any order, they are kept until all the results
+		// of the previous files have been reported
+		let mut pending = BTreeMap::new();
+		let mut next = 0;
+		for (index, result) in receiver {
+			pending.insert(index, result);
+			while let Some(result) = pending.remove(&next) {
+				report(&files[next], result);
+				next += 1;
+			}
+		}
+
+		// after an interruption, some files are missing: report the remaining results
+		for (index, result) in pending {
+			report(&files[index], result);
+		}
+	});
+
+	if is_interrupted() {
+		RunStatus::Interrupted
+	} else {
+		RunStatus::Completed
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::{process_files, RunStatus};
+	use std::path::PathBuf;
+
+	#[test]
+	fn reports_in_order() {
+		let files: Vec<PathBuf> = (0..50).map(|i| PathBuf::from(i.to_string())).collect();
+		let mut reported = Vec::new();
+
+		let status = process_files(
+			&files,
+			Some(4),
+			|path| path.display().to_string().parse::<usize>().unwrap() * 2,
+			|path, result| reported.push((path.to_path_buf(), result)),
+		);
+
+		assert_eq!(status, RunStatus::Completed);
+		let expected: Vec<_> = files
+			.iter()
+			.enumerate()
+			.map(|(index, path)| (path.clone(), index * 2))
+			.collect();
+		assert_eq!(reported, expected);
+	}
+}
@@ -0,0 +1,248 @@
+//! Resolves the inputs passed to the CLI into the list of files to process.
+//!
+//! An input can be:
+//! - a path to a file, which is processed as is
+//! - a path to a directory, which is walked recursively
+//! - a glob pattern, like `src/**/*.js`
+//!
+//! When walking a directory, the files matched by `.gitignore`, `.ignore` and `.romeignore`
+//! are skipped, following the semantics of `.gitignore`.
+use crate::configuration::FilesFilter;
+use globset::{GlobBuilder, GlobMatcher};
+use rome_path::{FileSystem, WalkOptions};
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Characters that make an input to be treated as glob pattern
+const GLOB_CHARACTERS: &[char] = &['*', '?', '[', '{'];
+
+/// Name of the ignore file specific to Rome
+const ROME_IGNORE_FILE_NAME: &str = ".romeignore";
+
+/// Returns `true` if the input should be treated as a glob pattern
+pub(crate) fn is_glob(input: &str) -> bool {
+	input.contains(GLOB_CHARACTERS)
+}
+
+/// The directory that contains the files matched by the input
+pub(crate) fn input_directory<'a>(fs: &dyn FileSystem, input: &'a str) -> &'a Path {
+	let path = if is_glob(input) {
+		Path::new(split_glob(input).0)
+	} else {
+		Path::new(input)
+	};
+
+	if matches!(fs.metadata(path), Ok(metadata) if metadata.is_file()) {
+		match path.parent() {
+			Some(parent) if parent != Path::new("") => parent,
+			_ => Path::new("."),
+		}
+	} else {
+		path
+	}
+}
+
+/// Options that control which files are collected
+#[derive(Default)]
+pub(crate) struct TraversalOptions<'a> {
+	/// Whether the ignore files are respected
+	pub(crate) respect_ignore_files: bool,
+	/// Whether the ignored paths are collected, so they can be reported
+	pub(crate) collect_ignored: bool,
+	/// The filter of the files configured in the project
+	pub(crate) filter: Option<&'a FilesFilter>,
+}
+
+/// The outcome of [collect_files]
+#[derive(Debug, Default)]
+pub(crate) struct CollectedFiles {
+	/// The files to process, sorted and deduplicated
+	pub(crate) files: Vec<PathBuf>,
+	/// The ignored files and directories, only collected when requested by [TraversalOptions]
+	pub(crate) ignored: Vec<PathBuf>,
+}
+
+/// Collects all the files matched by the inputs.
+///
+/// The returned files are sorted and deduplicated, so the same file is never processed twice.
+/// The paths that lead to the same file, like `./src/a.js` and `src/a.js`, are deduplicated as
+/// well, keeping the first one.
+pub(crate) fn collect_files<'a>(
+	fs: &dyn FileSystem,
+	inputs: impl IntoIterator<Item = &'a str>,
+	options: &TraversalOptions,
+) -> Result<CollectedFiles, String> {
+	let mut files = BTreeSet::new();
+	let mut ignored = BTreeSet::new();
+
+	for input in inputs {
+		if is_glob(input) {
+			let (base, pattern) = split_glob(input);
+			let glob = GlobBuilder::new(pattern)
+				.literal_separator(true)
+				.build()
+				.map_err(|err| format!("Invalid glob pattern \"{}\": {}", input, err))?
+				.compile_matcher();
+			collect_directory(
+				fs,
+				Path::new(base),
+				Some(&glob),
+				options,
+				&mut files,
+				&mut ignored,
+			);
+		} else {
+			let path = Path::new(input);
+			match fs.metadata(path) {
+				Ok(metadata) if metadata.is_dir() => {
+					collect_directory(fs, path, None, options, &mut files, &mut ignored)
+				}
+				Ok(_) => {
+					files.insert(path.to_path_buf());
+				}
+				Err(_) => return Err(format!("The path \"{}\" doesn't exist.", input)),
+			}
+		}
+	}
+
+	if let Some(filter) = options.filter {
+		let (included, excluded): (Vec<_>, Vec<_>) = files
+			.into_iter()
+			.partition(|path| filter.is_included(fs, path));
+		if options.collect_ignored {
+			ignored.extend(excluded);
+		}
+		files = included.into_iter().collect();
+	}
+
+	// a file that can't be canonicalized is kept, the error is reported when it's read
+	let mut canonical_paths = HashSet::new();
+	let files = files
+		.into_iter()
+		.filter(|path| {
+			fs.canonicalize(path)
+				.map_or(true, |canonical| canonical_paths.insert(canonical))
+		})
+		.collect();
+
+	Ok(CollectedFiles {
+		files,
+		ignored: ignored.into_iter().collect(),
+	})
+}
+
+/// Walks the directory, collecting the files that match `glob`, relative to `directory`, or
+/// all the files when there isn't a glob
+fn collect_directory(
+	fs: &dyn FileSystem,
+	directory: &Path,
+	glob: Option<&GlobMatcher>,
+	options: &TraversalOptions,
+	files: &mut BTreeSet<PathBuf>,
+	ignored: &mut BTreeSet<PathBuf>,
+) {
+	let matches = |path: &Path| {
+		glob.map_or(true, |glob| {
+			path.strip_prefix(directory)
+				.map_or(false, |relative| glob.is_match(relative))
+		})
+	};
+
+	let walk = fs.walk(
+		directory,
+		&WalkOptions {
+			respect_ignore_files: options.respect_ignore_files,
+			custom_ignore_files: &[ROME_IGNORE_FILE_NAME],
+			collect_ignored: options.collect_ignored,
+		},
+	);
+	files.extend(walk.files.into_iter().filter(|path| matches(path)));
+	// the ignored directories may contain files that match the glob
+	ignored.extend(walk.ignored_directories);
+	ignored.extend(walk.ignored_files.into_iter().filter(|path| matches(path)));
+}
+
+/// Splits a glob pattern in the directory where the walk should start, and the
+/// pattern relative to this directory.
+///
+/// For example, `src/**/*.js` becomes `("src", "**/*.js")`
+fn split_glob(pattern: &str) -> (&str, &str) {
+	let literal_end = pattern.find(GLOB_CHARACTERS).unwrap_or(pattern.len());
+	match pattern[..literal_end].rfind('/') {
+		Some(0) => ("/", &pattern[1..]),
+		Some(separator) => (&pattern[..separator], &pattern[separator + 1..]),
+		None => (".", pattern),
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::{collect_files, is_glob, split_glob, TraversalOptions};
+	use rome_path::MemoryFileSystem;
+	use std::path::PathBuf;
+
+	#[test]
+	fn detects_globs() {
+		assert!(is_glob("src/**/*.js"));
+		assert!(is_glob("file.{js,json}"));
+		assert!(!is_glob("src/file.js"));
+	}
+
+	#[test]
+	fn splits_glob_base() {
+		assert_eq!(split_glob("src/**/*.js"), ("src", "**/*.js"));
+		assert_eq!(split_glob("*.js"), (".", "*.js"));
+		assert_eq!(split_glob("/tmp/*.js"), ("/tmp", "*.js"));
+		assert_eq!(split_glob("/*.js"), ("/", "*.js"));
+	}
+
+	#[test]
+	fn respects_ignore_files() {
+		let fs = MemoryFileSystem::default()
+			.with_file("project/.gitignore", "node_modules\n")
+			.with_file("project/.romeignore", "*.min.js\n")
+			.with_file("project/node_modules/lib/index.js", "")
+			.with_file("project/src/index.js", "")
+			.with_file("project/src/index.min.js", "");
+
+		let options = TraversalOptions {
+			respect_ignore_files: true,
+			collect_ignored: true,
+			filter: None,
+		};
+		let collected = collect_files(&fs, vec!["project"], &options).unwrap();
+		assert_eq!(
+			collected.files,
+			vec![
+				PathBuf::from("project/.gitignore"),
+				PathBuf::from("project/.romeignore"),
+				PathBuf::from("project/src/index.js"),
+			]
+		);
+		assert_eq!(
+			collected.ignored,
+			vec![
+				PathBuf::from("project/node_modules"),
+				PathBuf::from("project/src/index.min.js"),
+			]
+		);
+
+		let collected = collect_files(&fs, vec!["project"], &TraversalOptions::default()).unwrap();
+		assert_eq!(collected.files.len(), 5);
+		assert!(collected.ignored.is_empty());
+	}
+
+	#[test]
+	fn deduplicates_files() {
+		let fs = MemoryFileSystem::default()
+			.with_file("src/a.js", "")
+			.with_file("src/b.js", "");
+
+		let inputs = vec!["./src", "src/a.js", "src/*.js"];
+		let collected = collect_files(&fs, inputs, &TraversalOptions::default()).unwrap();
+		assert_eq!(
+			collected.files,
+			vec![PathBuf::from("./src/a.js"), PathBuf::from("./src/b.js")]
+		);
+	}
+}
@@ -0,0 +1,133 @@
+//! Watches directories for changes, so the files can be processed again as soon as they are saved.
+//!
+//! The changes that happen close together are grouped in a single batch.
+//!
+//! The files written by Rome itself are ignored, otherwise formatting a file would trigger another
+//! run on the same file.
+use crate::runner::{install_interrupt_handler, is_interrupted};
+use rome_path::{FileSystem, WatchGuard};
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::time::{Duration, SystemTime};
+
+/// How long the session waits for more changes, before reporting a batch
+const BATCH_DELAY: Duration = Duration::from_millis(100);
+
+/// How often the session checks if it has been interrupted, while waiting for changes
+const INTERRUPT_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+pub(crate) struct WatchSession<'a> {
+	fs: &'a dyn FileSystem,
+	// the watcher stops when dropped
+	_watcher: WatchGuard,
+	changes: Receiver<PathBuf>,
+	/// The modification time of the files written by Rome, the changes of a file that
+	/// still has this modification time have been made by Rome
+	own_writes: HashMap<PathBuf, SystemTime>,
+}
+
+impl<'a> WatchSession<'a> {
+	/// Starts watching the directories, and their subdirectories
+	pub(crate) fn new(fs: &'a dyn FileSystem, directories: &[PathBuf]) -> Result<Self, String> {
+		install_interrupt_handler();
+
+		let (sender, changes) = channel();
+		let watcher = fs
+			.watch(directories, sender)
+			.map_err(|error| format!("Cannot watch the files: {}", error))?;
+
+		Ok(Self {
+			fs,
+			_watcher: watcher,
+			changes,
+			own_writes: HashMap::new(),
+		})
+	}
+
+	/// Records that Rome wrote the file, so the change isn't reported
+	pub(crate) fn record_write(&mut self, path: &Path) {
+		let path = match self.fs.canonicalize(path) {
+			Ok(path) => path,
+			Err(_) => return,
+		};
+		if let Some(modified) = self.modification_time(&path) {
+			self.own_writes.insert(path, modified);
+		}
+	}
+
+	/// Waits for the next batch of changes, returning the canonical paths of the files that
+	/// have been created or modified.
+	///
+	/// Returns [None] when the user has asked to stop the process
+	pub(crate) fn next_changes(&mut self) -> Option<Vec<PathBuf>> {
+		loop {
+			let change = match self.changes.recv_timeout(INTERRUPT_POLL_INTERVAL) {
+				Ok(change) => change,
+				Err(RecvTimeoutError::Timeout) => {
+					if is_interrupted() {
+						return None;
+					}
+					continue;
+				}
+				Err(RecvTimeoutError::Disconnected) => return None,
+			};
+
+			let mut changed = BTreeSet::new();
+			changed.insert(change);
+			// the changes that arrive shortly after are part of the same batch
+			while let Ok(change) = self.changes.recv_timeout(BATCH_DELAY) {
+				changed.insert(change);
+			}
+
+			let changes: Vec<_> = changed
+				.into_iter()
+				.filter(|path| !self.is_own_write(path))
+				.collect();
+			if !changes.is_empty() {
+				return Some(changes);
+			}
+		}
+	}
+
+	fn is_own_write(&mut self, path: &Path) -> bool {
+		match self.own_writes.get(path) {
+			Some(written) if self.modification_time(path).as_ref() == Some(written) => true,
+			Some(_) => {
+				// the file has been changed since Rome wrote it
+				self.own_writes.remove(path);
+				false
+			}
+			None => false,
+		}
+	}
+
+	fn modification_time(&self, path: &Path) -> Option<SystemTime> {
+		self.fs.metadata(path).ok()?.modified
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::WatchSession;
+	use rome_path::testing::TestDirectory;
+	use rome_path::OsFileSystem;
+	use std::fs;
+
+	#[test]
+	fn ignores_own_writes() {
+		let test_directory = TestDirectory::new("watch_own_writes");
+		let directory = test_directory.canonicalize().unwrap();
+
+		let mut session =
+			WatchSession::new(&OsFileSystem, std::slice::from_ref(&directory)).unwrap();
+		fs::write(directory.join("formatted.js"), "let a = 1;\n").unwrap();
+		session.record_write(&directory.join("formatted.js"));
+		fs::write(directory.join("changed.js"), "let a  =  1\n").unwrap();
+
+		assert_eq!(
+			session.next_changes(),
+			Some(vec![directory.join("changed.js")])
+		);
+	}
+}
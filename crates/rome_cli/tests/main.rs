@@ -1,4 +1,10 @@
-use std::{env, path::PathBuf, process::Command};
+use rome_path::testing::TestDirectory;
+use std::{
+	env, fs,
+	io::Write,
+	path::PathBuf,
+	process::{Command, Stdio},
+};
 
 fn cargo_bin(name: &str) -> PathBuf {
 	env::current_exe()
@@ -34,3 +40,851 @@ fn test_format_cli() {
 "#
 	);
 }
+
+#[test]
+fn test_format_directory() {
+	let directory = TestDirectory::new("cli_format_directory");
+	fs::create_dir(directory.join("nested")).unwrap();
+	fs::write(directory.join("nested/unformatted.js"), "let a  =  1").unwrap();
+	fs::write(directory.join("formatted.json"), "{\"a\": 1}\n").unwrap();
+	fs::write(directory.join("notes.txt"), "not a source file").unwrap();
+
+	let res = Command::new(cargo_bin("cli"))
+		.arg("format")
+		.arg(directory.path())
+		.output()
+		.expect("fail to run cli format");
+
+	assert!(res.status.success(), "cli format command failed");
+	let output = String::from_utf8(res.stdout).expect("cannot read stdout, not utf8 compliant");
+	assert_eq!(
+		output,
+		"Processed 3 files: 1 formatted, 1 unchanged, 1 skipped, 0 failed\n"
+	);
+	assert_eq!(
+		fs::read_to_string(directory.join("nested/unformatted.js")).unwrap(),
+		"let a = 1;\n"
+	);
+}
+
+#[test]
+fn test_format_without_extension() {
+	let directory = TestDirectory::new("cli_format_without_extension");
+	fs::create_dir(directory.join("bin")).unwrap();
+	fs::write(
+		directory.join("bin/deploy"),
+		"#!/usr/bin/env node\nlet a  =  1",
+	)
+	.unwrap();
+	fs::write(directory.join(".eslintrc"), "{\"root\":  true}").unwrap();
+	fs::write(directory.join("LICENSE"), "MIT License").unwrap();
+
+	let res = Command::new(cargo_bin("cli"))
+		.arg("format")
+		.arg(directory.path())
+		.output()
+		.expect("fail to run cli format");
+
+	assert!(res.status.success(), "cli format command failed");
+	let output = String::from_utf8(res.stdout).expect("cannot read stdout, not utf8 compliant");
+	assert_eq!(
+		output,
+		"Processed 3 files: 2 formatted, 0 unchanged, 1 skipped, 0 failed\n"
+	);
+	assert_eq!(
+		fs::read_to_string(directory.join("bin/deploy")).unwrap(),
+		"#!/usr/bin/env node\nlet a = 1;\n"
+	);
+}
+
+#[test]
+fn test_format_glob_pattern() {
+	let directory = TestDirectory::new("cli_format_glob_pattern");
+	fs::write(directory.join("first.js"), "let a  =  1").unwrap();
+	fs::write(directory.join("second.js"), "let b  =  2").unwrap();
+	fs::write(directory.join("ignored.json"), "{\"a\":   1}").unwrap();
+
+	let pattern = format!("{}/*.js", directory.display());
+	let res = Command::new(cargo_bin("cli"))
+		.args(&["format", &pattern])
+		.output()
+		.expect("fail to run cli format");
+
+	assert!(res.status.success(), "cli format command failed");
+	let output = String::from_utf8(res.stdout).expect("cannot read stdout, not utf8 compliant");
+	assert_eq!(
+		output,
+		"Processed 2 files: 2 formatted, 0 unchanged, 0 skipped, 0 failed\n"
+	);
+	assert_eq!(
+		fs::read_to_string(directory.join("ignored.json")).unwrap(),
+		"{\"a\":   1}"
+	);
+}
+
+#[test]
+fn test_format_check() {
+	let directory = TestDirectory::new("cli_format_check");
+	let file = directory.join("unformatted.js");
+	fs::write(&file, "let a  =  1\n").unwrap();
+
+	let res = Command::new(cargo_bin("cli"))
+		.args(&["format", "--check"])
+		.arg(&file)
+		.output()
+		.expect("fail to run cli format");
+
+	assert_eq!(res.status.code(), Some(3), "cli format --check should fail");
+	let output = String::from_utf8(res.stdout).expect("cannot read stdout, not utf8 compliant");
+	assert!(output.starts_with(&format!(
+		"error[format]: The file \"{}\" is not formatted\n",
+		file.display()
+	)));
+	// the diff is printed after the diagnostic, without colors as stdout isn't a terminal
+	assert!(output.contains(&format!("--- {}", file.display())));
+	assert!(output.contains("@@ -1 +1 @@"));
+	assert!(output.contains("-let a  =  1"));
+	assert!(output.contains("+let a = 1;"));
+	assert!(
+		output.ends_with("Checked 1 files: 1 not formatted, 0 formatted, 0 skipped, 0 failed\n")
+	);
+	assert_eq!(fs::read_to_string(&file).unwrap(), "let a  =  1\n");
+}
+
+#[test]
+fn test_format_stdin() {
+	let mut child = Command::new(cargo_bin("cli"))
+		.args(&["format", "--stdin-file-path", "buffer.js"])
+		.stdin(Stdio::piped())
+		.stdout(Stdio::piped())
+		.spawn()
+		.expect("fail to run cli format");
+
+	child
+		.stdin
+		.take()
+		.unwrap()
+		.write_all(b"let a  =  1")
+		.expect("cannot write on stdin");
+
+	let res = child.wait_with_output().expect("fail to run cli format");
+	assert!(res.status.success(), "cli format command failed");
+	let output = String::from_utf8(res.stdout).expect("cannot read stdout, not utf8 compliant");
+	assert_eq!(output, "let a = 1;\n");
+}
+
+#[test]
+fn test_format_threads_ordered_output() {
+	let directory = TestDirectory::new("cli_format_threads_ordered_output");
+	for index in 0..20 {
+		fs::write(
+			directory.join(format!("file_{:02}.js", index)),
+			"let a  =  1\n",
+		)
+		.unwrap();
+	}
+
+	let res = Command::new(cargo_bin("cli"))
+		.args(&["format", "--check", "--threads", "4"])
+		.arg(directory.path())
+		.output()
+		.expect("fail to run cli format");
+
+	assert_eq!(res.status.code(), Some(3), "cli format --check should fail");
+	let output = String::from_utf8(res.stdout).expect("cannot read stdout, not utf8 compliant");
+	let reported: Vec<_> = output
+		.lines()
+		.filter(|line| line.starts_with("--- "))
+		.collect();
+	let expected: Vec<_> = (0..20)
+		.map(|index| {
+			format!(
+				"--- {}",
+				directory.join(format!("file_{:02}.js", index)).display()
+			)
+		})
+		.collect();
+	assert_eq!(reported, expected);
+}
+
+#[test]
+fn test_format_with_configuration() {
+	let directory = TestDirectory::new("cli_format_with_configuration");
+	fs::write(
+		directory.join("rome.json"),
+		r#"{
+	"formatter": { "quoteStyle": "single" },
+	"files": { "ignore": ["ignored/**"] }
+}"#,
+	)
+	.unwrap();
+	fs::create_dir(directory.join("ignored")).unwrap();
+	fs::write(directory.join("file.js"), "let a  =  \"b\"").unwrap();
+	fs::write(directory.join("ignored").join("file.js"), "let a  =  \"b\"").unwrap();
+
+	let res = Command::new(cargo_bin("cli"))
+		.args(&["format", "."])
+		.current_dir(&directory)
+		.output()
+		.expect("fail to run cli format");
+
+	assert!(res.status.success(), "cli format command failed");
+	assert_eq!(
+		fs::read_to_string(directory.join("file.js")).unwrap(),
+		"let a = 'b';\n"
+	);
+	assert_eq!(
+		fs::read_to_string(directory.join("ignored").join("file.js")).unwrap(),
+		"let a  =  \"b\""
+	);
+
+	// the arguments override the configuration
+	let mut child = Command::new(cargo_bin("cli"))
+		.args(&[
+			"format",
+			"--stdin-file-path",
+			"buffer.js",
+			"--quote-style",
+			"double",
+		])
+		.current_dir(&directory)
+		.stdin(Stdio::piped())
+		.stdout(Stdio::piped())
+		.spawn()
+		.expect("fail to run cli format");
+	child
+		.stdin
+		.take()
+		.unwrap()
+		.write_all(b"let a  =  'b'")
+		.expect("cannot write on stdin");
+
+	let res = child.wait_with_output().expect("fail to run cli format");
+	assert!(res.status.success(), "cli format command failed");
+	let output = String::from_utf8(res.stdout).expect("cannot read stdout, not utf8 compliant");
+	assert_eq!(output, "let a = \"b\";\n");
+}
+
+#[test]
+fn test_format_invalid_configuration() {
+	let directory = TestDirectory::new("cli_format_invalid_configuration");
+	fs::write(
+		directory.join("rome.json"),
+		r#"{ "formatter": { "lineWidth": "80" } }"#,
+	)
+	.unwrap();
+	fs::write(directory.join("file.js"), "let a  =  1").unwrap();
+
+	let res = Command::new(cargo_bin("cli"))
+		.args(&["format", "."])
+		.current_dir(&directory)
+		.output()
+		.expect("fail to run cli format");
+
+	assert_eq!(res.status.code(), Some(1), "cli format should fail");
+	let output = String::from_utf8(res.stderr).expect("cannot read stderr, not utf8 compliant");
+	assert!(
+		output.contains("Invalid type for \"lineWidth\""),
+		"unexpected output: {}",
+		output
+	);
+	assert_eq!(
+		fs::read_to_string(directory.join("file.js")).unwrap(),
+		"let a  =  1"
+	);
+}
+
+#[test]
+fn test_init() {
+	let directory = TestDirectory::new("cli_init");
+	fs::write(directory.join(".prettierrc"), r#"{ "singleQuote": true }"#).unwrap();
+
+	let res = Command::new(cargo_bin("cli"))
+		.arg("init")
+		.current_dir(&directory)
+		.output()
+		.expect("fail to run cli init");
+
+	assert!(res.status.success(), "cli init command failed");
+	let output = String::from_utf8(res.stdout).expect("cannot read stdout, not utf8 compliant");
+	assert_eq!(
+		output,
+		"Created rome.json, using the options of .prettierrc\n"
+	);
+	let configuration = fs::read_to_string(directory.join("rome.json")).unwrap();
+	assert!(configuration.contains(r#""quoteStyle": "single""#));
+
+	// an existing configuration is only overwritten with --force
+	fs::write(directory.join("rome.json"), "{}").unwrap();
+	let res = Command::new(cargo_bin("cli"))
+		.arg("init")
+		.current_dir(&directory)
+		.output()
+		.expect("fail to run cli init");
+	assert_eq!(res.status.code(), Some(1), "cli init should fail");
+	assert_eq!(
+		fs::read_to_string(directory.join("rome.json")).unwrap(),
+		"{}"
+	);
+
+	let res = Command::new(cargo_bin("cli"))
+		.args(&["init", "--force"])
+		.current_dir(&directory)
+		.output()
+		.expect("fail to run cli init");
+	assert!(res.status.success(), "cli init --force command failed");
+	assert_eq!(
+		fs::read_to_string(directory.join("rome.json")).unwrap(),
+		configuration
+	);
+}
+
+#[test]
+fn test_init_then_format() {
+	let directory = TestDirectory::new("cli_init_then_format");
+	fs::write(directory.join("file.js"), "let a  =  1").unwrap();
+
+	let res = Command::new(cargo_bin("cli"))
+		.arg("init")
+		.current_dir(&directory)
+		.output()
+		.expect("fail to run cli init");
+	assert!(res.status.success(), "cli init command failed");
+	let configuration = fs::read_to_string(directory.join("rome.json")).unwrap();
+
+	let res = Command::new(cargo_bin("cli"))
+		.args(&["format", "."])
+		.current_dir(&directory)
+		.output()
+		.expect("fail to run cli format");
+
+	assert!(res.status.success(), "cli format command failed");
+	let output = String::from_utf8(res.stdout).expect("cannot read stdout, not utf8 compliant");
+	// the configuration file can contain comments, the formatter would drop them
+	assert_eq!(
+		output,
+		"Processed 2 files: 1 formatted, 0 unchanged, 1 skipped, 0 failed\n"
+	);
+	assert_eq!(
+		fs::read_to_string(directory.join("rome.json")).unwrap(),
+		configuration
+	);
+}
+
+#[test]
+fn test_format_ignore_files() {
+	let directory = TestDirectory::new("cli_format_ignore_files");
+	fs::create_dir(directory.join("dist")).unwrap();
+	fs::write(directory.join(".gitignore"), "dist/\n").unwrap();
+	fs::write(directory.join("dist").join("file.js"), "let a  =  1").unwrap();
+	fs::write(directory.join("file.js"), "let a  =  1").unwrap();
+
+	let res = Command::new(cargo_bin("cli"))
+		.args(&["format", "--verbose", "."])
+		.current_dir(&directory)
+		.output()
+		.expect("fail to run cli format");
+
+	assert!(res.status.success(), "cli format command failed");
+	let output = String::from_utf8(res.stderr).expect("cannot read stderr, not utf8 compliant");
+	assert_eq!(
+		output,
+		"Ignored \"./dist\"\nSkipped \"./.gitignore\", the file can't be formatted\n"
+	);
+	assert_eq!(
+		fs::read_to_string(directory.join("file.js")).unwrap(),
+		"let a = 1;\n"
+	);
+	assert_eq!(
+		fs::read_to_string(directory.join("dist").join("file.js")).unwrap(),
+		"let a  =  1"
+	);
+
+	let res = Command::new(cargo_bin("cli"))
+		.args(&["format", "--no-ignore", "."])
+		.current_dir(&directory)
+		.output()
+		.expect("fail to run cli format");
+
+	assert!(res.status.success(), "cli format command failed");
+	assert_eq!(
+		fs::read_to_string(directory.join("dist").join("file.js")).unwrap(),
+		"let a = 1;\n"
+	);
+}
+
+#[test]
+fn test_format_with_editorconfig() {
+	let directory = TestDirectory::new("cli_format_with_editorconfig");
+	fs::write(
+		directory.join(".editorconfig"),
+		"root = true\n\n[*.js]\nindent_style = space\nindent_size = 4\n",
+	)
+	.unwrap();
+	fs::write(
+		directory.join("rome.json"),
+		r#"{ "javascript": { "formatter": { "indentSize": 2 } } }"#,
+	)
+	.unwrap();
+	fs::write(directory.join("file.js"), "function f() { return 1 }").unwrap();
+
+	let res = Command::new(cargo_bin("cli"))
+		.args(&["format", "file.js"])
+		.current_dir(&directory)
+		.output()
+		.expect("fail to run cli format");
+
+	assert!(res.status.success(), "cli format command failed");
+	// the indent style comes from .editorconfig, while the size is overridden by rome.json
+	assert_eq!(
+		fs::read_to_string(directory.join("file.js")).unwrap(),
+		"function f() {\n  return 1;\n}\n"
+	);
+}
+
+#[test]
+fn test_parse_tokens() {
+	let directory = TestDirectory::new("cli_parse_tokens");
+	let file = directory.join("file.js");
+	fs::write(&file, "a = 1").unwrap();
+
+	let res = Command::new(cargo_bin("cli"))
+		.args(&["parse", "--tokens"])
+		.arg(&file)
+		.output()
+		.expect("fail to run cli parse");
+
+	assert!(res.status.success(), "cli parse command failed");
+	let output = String::from_utf8(res.stdout).expect("cannot read stdout, not utf8 compliant");
+	assert_eq!(
+		output,
+		"IDENT@0..1 \"a\"\nWHITESPACE@1..2 \" \"\nEQ@2..3 \"=\"\nWHITESPACE@3..4 \" \"\nNUMBER@4..5 \"1\"\nEOF@5..5 \"\"\n"
+	);
+}
+
+#[test]
+fn test_parse_diagnostics() {
+	let directory = TestDirectory::new("cli_parse_diagnostics");
+	let file = directory.join("file.js");
+	fs::write(&file, "import a from \"a\";").unwrap();
+
+	let res = Command::new(cargo_bin("cli"))
+		.arg("parse")
+		.arg(&file)
+		.output()
+		.expect("fail to run cli parse");
+	assert_eq!(res.status.code(), Some(1), "cli parse should fail");
+
+	let res = Command::new(cargo_bin("cli"))
+		.args(&["parse", "--source-type", "module"])
+		.arg(&file)
+		.output()
+		.expect("fail to run cli parse");
+	assert!(res.status.success(), "cli parse command failed");
+	let output = String::from_utf8(res.stdout).expect("cannot read stdout, not utf8 compliant");
+	assert_eq!(
+		output,
+		format!("Found 0 diagnostics in \"{}\"\n", file.display())
+	);
+}
+
+#[test]
+fn test_lint() {
+	let directory = TestDirectory::new("cli_lint");
+	fs::write(directory.join("debug.js"), "debugger;\n").unwrap();
+	fs::write(directory.join("clean.js"), "let a = [1, 2];\n").unwrap();
+	fs::write(directory.join("notes.txt"), "not a source file").unwrap();
+
+	let res = Command::new(cargo_bin("cli"))
+		.arg("lint")
+		.arg(directory.path())
+		.output()
+		.expect("fail to run cli lint");
+
+	assert_eq!(res.status.code(), Some(3), "cli lint should fail");
+	let output = String::from_utf8(res.stdout).expect("cannot read stdout, not utf8 compliant");
+	assert!(output.contains("error[lint/noDebugger]"));
+	assert!(output.ends_with("Linted 2 files: 1 errors, 0 warnings, 1 skipped, 0 failed\n"));
+}
+
+#[test]
+fn test_lint_json_reporter() {
+	let directory = TestDirectory::new("cli_lint_json_reporter");
+	let file = directory.join("debug.js");
+	fs::write(&file, "debugger;\n").unwrap();
+
+	let res = Command::new(cargo_bin("cli"))
+		.args(&["lint", "--reporter", "json"])
+		.arg(&file)
+		.output()
+		.expect("fail to run cli lint");
+
+	assert_eq!(res.status.code(), Some(3), "cli lint should fail");
+	let output = String::from_utf8(res.stdout).expect("cannot read stdout, not utf8 compliant");
+	let records: Vec<&str> = output.lines().collect();
+	assert_eq!(records.len(), 3);
+	assert!(records[0].contains("\"code\":\"lint/noDebugger\""));
+	assert!(records[1].starts_with(&format!(
+		"{{\"type\":\"file\",\"path\":\"{}\",\"status\":\"linted\",\"duration_ms\":",
+		file.display()
+	)));
+	assert_eq!(
+		records[2],
+		"{\"type\":\"summary\",\"mode\":\"lint\",\"total\":1,\"linted\":1,\"errors\":1,\"warnings\":0,\"fixes\":0,\"skipped\":0,\"failed\":0}"
+	);
+}
+
+#[test]
+fn test_lint_apply() {
+	let directory = TestDirectory::new("cli_lint_apply");
+	let file = directory.join("index.js");
+	fs::write(&file, "debugger;\nif (a == b) {}\n").unwrap();
+
+	let res = Command::new(cargo_bin("cli"))
+		.args(&["lint", "--apply"])
+		.arg(&file)
+		.output()
+		.expect("fail to run cli lint");
+
+	// the unsafe fix of noDoubleEquals is left to the user
+	assert_eq!(res.status.code(), Some(3), "cli lint --apply should fail");
+	assert_eq!(fs::read_to_string(&file).unwrap(), "\nif (a == b) {}\n");
+
+	let res = Command::new(cargo_bin("cli"))
+		.args(&["lint", "--apply-unsafe"])
+		.arg(&file)
+		.output()
+		.expect("fail to run cli lint");
+
+	assert!(
+		res.status.success(),
+		"cli lint --apply-unsafe should succeed"
+	);
+	assert_eq!(fs::read_to_string(&file).unwrap(), "\nif (a === b) {}\n");
+	let output = String::from_utf8(res.stdout).expect("cannot read stdout, not utf8 compliant");
+	assert!(output
+		.ends_with("Applied 1 fixes\nLinted 1 files: 0 errors, 0 warnings, 0 skipped, 0 failed\n"));
+}
+
+#[test]
+fn test_format_json_reporter() {
+	let directory = TestDirectory::new("cli_format_json_reporter");
+	let file = directory.join("unformatted.js");
+	fs::write(&file, "let a  =  1\n").unwrap();
+
+	let res = Command::new(cargo_bin("cli"))
+		.args(&["format", "--check", "--reporter", "json"])
+		.arg(&file)
+		.output()
+		.expect("fail to run cli format");
+
+	assert_eq!(res.status.code(), Some(3), "cli format --check should fail");
+	let output = String::from_utf8(res.stdout).expect("cannot read stdout, not utf8 compliant");
+	let records: Vec<&str> = output.lines().collect();
+	assert_eq!(records.len(), 3);
+	assert!(records[0].starts_with(&format!(
+		"{{\"type\":\"diagnostic\",\"file\":\"{}\",",
+		file.display()
+	)));
+	assert!(records[0].contains("\"code\":\"format\""));
+	assert!(records[1].starts_with(&format!(
+		"{{\"type\":\"file\",\"path\":\"{}\",\"status\":\"not_formatted\",\"duration_ms\":",
+		file.display()
+	)));
+	assert_eq!(
+		records[2],
+		"{\"type\":\"summary\",\"mode\":\"check\",\"total\":1,\"formatted\":1,\"unchanged\":0,\"skipped\":0,\"failed\":0}"
+	);
+}
+
+#[test]
+fn test_format_staged() {
+	let directory = TestDirectory::new("cli_format_staged");
+	let git = |args: &[&str]| {
+		let res = Command::new("git")
+			.args(args)
+			.current_dir(&directory)
+			.output()
+			.expect("fail to run git");
+		assert!(res.status.success(), "git command failed");
+		String::from_utf8(res.stdout).expect("cannot read stdout, not utf8 compliant")
+	};
+	git(&["init", "--quiet"]);
+	fs::write(directory.join("staged.js"), "let a  =  1\n").unwrap();
+	fs::write(directory.join("partial.js"), "let b  =  2\n").unwrap();
+	git(&["add", "staged.js", "partial.js"]);
+	fs::write(directory.join("partial.js"), "let b  =  2\nlet c  =  3\n").unwrap();
+	fs::write(directory.join("untracked.js"), "let d  =  4\n").unwrap();
+
+	let res = Command::new(cargo_bin("cli"))
+		.args(&["format", "--staged", "."])
+		.current_dir(&directory)
+		.output()
+		.expect("fail to run cli format");
+
+	assert!(res.status.success(), "cli format --staged command failed");
+	assert_eq!(git(&["show", ":staged.js"]), "let a = 1;\n");
+	assert_eq!(git(&["show", ":partial.js"]), "let b = 2;\n");
+	assert_eq!(
+		fs::read_to_string(directory.join("staged.js")).unwrap(),
+		"let a = 1;\n"
+	);
+	// the unstaged changes are kept, and the files that aren't staged are untouched
+	assert_eq!(
+		fs::read_to_string(directory.join("partial.js")).unwrap(),
+		"let b  =  2\nlet c  =  3\n"
+	);
+	assert_eq!(
+		fs::read_to_string(directory.join("untracked.js")).unwrap(),
+		"let d  =  4\n"
+	);
+}
+
+#[test]
+fn test_format_cache() {
+	let directory = TestDirectory::new("cli_format_cache");
+	fs::create_dir(directory.join("node_modules")).unwrap();
+	fs::write(directory.join("index.js"), "let a = 1;\n").unwrap();
+
+	for _ in 0..2 {
+		let res = Command::new(cargo_bin("cli"))
+			.args(&["format", "--check", "."])
+			.current_dir(&directory)
+			.output()
+			.expect("fail to run cli format");
+
+		assert!(res.status.success(), "cli format --check command failed");
+		let output = String::from_utf8(res.stdout).expect("cannot read stdout, not utf8 compliant");
+		assert_eq!(
+			output,
+			"Checked 1 files: 0 not formatted, 1 formatted, 0 skipped, 0 failed\n"
+		);
+	}
+
+	let cache = fs::read_to_string(directory.join("node_modules/.cache/rome/format.json"))
+		.expect("cannot read the cache");
+	assert!(cache.contains("index.js"));
+}
+
+#[test]
+fn test_lint_cache() {
+	let directory = TestDirectory::new("cli_lint_cache");
+	fs::write(directory.join("clean.js"), "let a = 1;\n").unwrap();
+	fs::write(directory.join("debugger.js"), "debugger;\n").unwrap();
+
+	let res = Command::new(cargo_bin("cli"))
+		.args(&["lint", "--no-cache", "."])
+		.current_dir(&directory)
+		.output()
+		.expect("fail to run cli lint");
+	assert_eq!(res.status.code(), Some(3), "cli lint should fail");
+	assert!(!directory.join("cache").exists());
+
+	for _ in 0..2 {
+		let res = Command::new(cargo_bin("cli"))
+			.args(&["lint", "--cache-dir", "cache", "."])
+			.current_dir(&directory)
+			.output()
+			.expect("fail to run cli lint");
+
+		// the diagnostics of the files that aren't clean are reported by every run
+		assert_eq!(res.status.code(), Some(3), "cli lint should fail");
+		let output = String::from_utf8(res.stdout).expect("cannot read stdout, not utf8 compliant");
+		assert!(output.contains("error[lint/noDebugger]"));
+		assert!(output.ends_with("Linted 2 files: 1 errors, 0 warnings, 0 skipped, 0 failed\n"));
+	}
+
+	let cache =
+		fs::read_to_string(directory.join("cache/lint.json")).expect("cannot read the cache");
+	assert!(cache.contains("clean.js"));
+	assert!(!cache.contains("debugger.js"));
+}
+
+#[test]
+fn test_format_invalid_utf8() {
+	let directory = TestDirectory::new("cli_format_invalid_utf8");
+	fs::write(directory.join("invalid.js"), b"let a = \"\xff\";").unwrap();
+	fs::write(directory.join("valid.js"), "let b  =  1\n").unwrap();
+
+	let res = Command::new(cargo_bin("cli"))
+		.args(&["format", "."])
+		.current_dir(&directory)
+		.output()
+		.expect("fail to run cli format");
+
+	assert_eq!(res.status.code(), Some(1), "cli format should fail");
+	let output = String::from_utf8(res.stdout).expect("cannot read stdout, not utf8 compliant");
+	assert!(output.starts_with("error[io]: The file \"./invalid.js\" isn't valid UTF-8\n"));
+	assert!(output.contains("the first invalid byte is at offset 9"));
+	assert!(output.ends_with("Processed 2 files: 1 formatted, 0 unchanged, 0 skipped, 1 failed\n"));
+	// the other files are still formatted
+	assert_eq!(
+		fs::read_to_string(directory.join("valid.js")).unwrap(),
+		"let b = 1;\n"
+	);
+}
+
+#[test]
+fn test_format_byte_order_mark() {
+	let directory = TestDirectory::new("cli_format_byte_order_mark");
+	fs::write(directory.join("kept.js"), "\u{feff}let a  =  1\n").unwrap();
+	fs::write(directory.join("utf16.js"), b"\xff\xfel\0e\0t\0").unwrap();
+
+	let res = Command::new(cargo_bin("cli"))
+		.args(&["format", "."])
+		.current_dir(&directory)
+		.output()
+		.expect("fail to run cli format");
+
+	assert_eq!(res.status.code(), Some(1), "cli format should fail");
+	let output = String::from_utf8(res.stdout).expect("cannot read stdout, not utf8 compliant");
+	assert!(output.starts_with("error[io]: The file \"./utf16.js\" is encoded in UTF-16\n"));
+	assert_eq!(
+		fs::read_to_string(directory.join("kept.js")).unwrap(),
+		"\u{feff}let a = 1;\n"
+	);
+
+	let res = Command::new(cargo_bin("cli"))
+		.args(&["format", "--byte-order-mark=remove", "kept.js"])
+		.current_dir(&directory)
+		.output()
+		.expect("fail to run cli format");
+
+	assert!(res.status.success(), "cli format should succeed");
+	assert_eq!(
+		fs::read_to_string(directory.join("kept.js")).unwrap(),
+		"let a = 1;\n"
+	);
+}
+
+/// Frames a message of the language server protocol
+fn lsp_message(content: serde_json::Value) -> String {
+	let content = content.to_string();
+	format!("Content-Length: {}\r\n\r\n{}", content.len(), content)
+}
+
+/// Splits the output of the language server into its messages
+fn lsp_messages(mut output: &str) -> Vec<serde_json::Value> {
+	let mut messages = Vec::new();
+	while let Some(header_end) = output.find("\r\n\r\n") {
+		let length: usize = output[..header_end]
+			.trim_start_matches("Content-Length: ")
+			.parse()
+			.expect("invalid Content-Length header");
+		let content = &output[header_end + 4..header_end + 4 + length];
+		messages.push(serde_json::from_str(content).expect("invalid message"));
+		output = &output[header_end + 4 + length..];
+	}
+	messages
+}
+
+#[test]
+fn test_lsp() {
+	use serde_json::json;
+
+	let test_directory = TestDirectory::new("cli_lsp");
+	let directory = test_directory.canonicalize().unwrap();
+	fs::write(
+		directory.join("rome.json"),
+		r#"{ "formatter": { "quoteStyle": "single" } }"#,
+	)
+	.unwrap();
+	let root = format!("file://{}", directory.display());
+	let uri = format!("{}/index.js", root);
+	let options = json!({ "tabSize": 4, "insertSpaces": true });
+
+	let input = [
+		json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": { "rootUri": root, "capabilities": {} } }),
+		json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }),
+		json!({ "jsonrpc": "2.0", "method": "textDocument/didOpen", "params": { "textDocument": {
+			"uri": uri, "languageId": "javascript", "version": 1, "text": "let a  =  \"a\"\nlet b = (\n"
+		} } }),
+		json!({ "jsonrpc": "2.0", "id": 2, "method": "textDocument/formatting", "params": {
+			"textDocument": { "uri": uri }, "options": options
+		} }),
+		json!({ "jsonrpc": "2.0", "method": "textDocument/didChange", "params": {
+			"textDocument": { "uri": uri, "version": 2 },
+			"contentChanges": [{ "range": { "start": { "line": 1, "character": 8 }, "end": { "line": 2, "character": 0 } }, "text": "2\nif (b) {\nb()\n}\n" }]
+		} }),
+		json!({ "jsonrpc": "2.0", "id": 3, "method": "textDocument/formatting", "params": {
+			"textDocument": { "uri": uri }, "options": options
+		} }),
+		json!({ "jsonrpc": "2.0", "id": 4, "method": "textDocument/rangeFormatting", "params": {
+			"textDocument": { "uri": uri },
+			"range": { "start": { "line": 3, "character": 0 }, "end": { "line": 3, "character": 3 } },
+			"options": options
+		} }),
+		json!({ "jsonrpc": "2.0", "id": 5, "method": "textDocument/semanticTokens/range", "params": {
+			"textDocument": { "uri": uri },
+			"range": { "start": { "line": 3, "character": 0 }, "end": { "line": 3, "character": 3 } }
+		} }),
+		json!({ "jsonrpc": "2.0", "id": 6, "method": "shutdown" }),
+		json!({ "jsonrpc": "2.0", "method": "exit" }),
+	];
+
+	let mut child = Command::new(cargo_bin("cli"))
+		.args(&["lsp", "--stdio"])
+		.current_dir(&directory)
+		.stdin(Stdio::piped())
+		.stdout(Stdio::piped())
+		.spawn()
+		.expect("fail to run cli lsp");
+	let messages: String = input.iter().cloned().map(lsp_message).collect();
+	child
+		.stdin
+		.take()
+		.unwrap()
+		.write_all(messages.as_bytes())
+		.unwrap();
+	let res = child.wait_with_output().unwrap();
+	assert!(res.status.success(), "cli lsp should exit successfully");
+
+	let output = String::from_utf8(res.stdout).expect("cannot read stdout, not utf8 compliant");
+	let messages = lsp_messages(&output);
+	assert_eq!(messages.len(), 8);
+
+	assert_eq!(messages[0]["id"], 1);
+	assert_eq!(messages[0]["result"]["serverInfo"]["name"], "rome");
+	assert_eq!(
+		messages[0]["result"]["capabilities"]["documentFormattingProvider"],
+		true
+	);
+	let legend = &messages[0]["result"]["capabilities"]["semanticTokensProvider"]["legend"];
+	assert_eq!(legend["tokenTypes"][5], "function");
+	assert_eq!(legend["tokenModifiers"][0], "declaration");
+
+	// the unfinished code has a syntax error, and isn't formatted
+	assert_eq!(messages[1]["method"], "textDocument/publishDiagnostics");
+	assert_eq!(messages[1]["params"]["version"], 1);
+	let diagnostics = messages[1]["params"]["diagnostics"].as_array().unwrap();
+	assert_eq!(diagnostics.len(), 2);
+	assert_eq!(diagnostics[0]["range"]["start"]["line"], 2);
+	assert_eq!(messages[2]["id"], 2);
+	assert_eq!(messages[2]["result"], json!(null));
+
+	assert_eq!(messages[3]["params"]["version"], 2);
+	assert_eq!(messages[3]["params"]["diagnostics"], json!([]));
+
+	// the configuration takes precedence over the options of the editor
+	assert_eq!(
+		messages[4]["result"],
+		json!([
+			{
+				"range": { "start": { "line": 0, "character": 0 }, "end": { "line": 2, "character": 0 } },
+				"newText": "let a = 'a';\nlet b = 2;\n"
+			},
+			{
+				"range": { "start": { "line": 3, "character": 0 }, "end": { "line": 4, "character": 0 } },
+				"newText": "    b();\n"
+			}
+		])
+	);
+	assert_eq!(messages[5]["result"], json!([messages[4]["result"][1]]));
+
+	// the call of `b`, on the fourth line
+	assert_eq!(messages[6]["id"], 5);
+	assert_eq!(messages[6]["result"], json!({ "data": [3, 0, 1, 5, 0] }));
+
+	assert_eq!(messages[7]["id"], 6);
+	assert_eq!(messages[7]["result"], json!(null));
+}
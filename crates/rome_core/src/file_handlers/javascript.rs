@@ -1,12 +1,65 @@
-use super::{ExtensionHandler, Mime};
+use super::{ExtensionHandler, Mime, Parse, SourceType};
+use rome_formatter::{FormatError, FormatOptions, FormatResult, Formatted, Formatter};
+use rslint_errors::file::FileId;
+use rslint_parser::{parse_with_syntax, Syntax};
 
 #[derive(Debug, PartialEq, Eq)]
-pub struct JsFileHandler {}
+pub struct JsFileHandler {
+	extensions: &'static [&'static str],
+	interpreters: &'static [&'static str],
+	source_type: SourceType,
+}
+
+impl JsFileHandler {
+	/// The scripts: the `.js` and `.cjs` files, and the files run by Node.js or Bun
+	pub fn script() -> Self {
+		Self {
+			extensions: &["js", "cjs"],
+			interpreters: &["node", "nodejs", "bun"],
+			source_type: SourceType::default(),
+		}
+	}
+
+	/// The ECMAScript modules, the `.mjs` files
+	pub fn module() -> Self {
+		Self {
+			extensions: &["mjs"],
+			interpreters: &[],
+			source_type: SourceType {
+				module: true,
+				..SourceType::default()
+			},
+		}
+	}
+
+	/// The `.jsx` files
+	pub fn jsx() -> Self {
+		Self {
+			extensions: &["jsx"],
+			interpreters: &[],
+			source_type: SourceType {
+				jsx: true,
+				..SourceType::default()
+			},
+		}
+	}
+
+	/// The formatter doesn't support the modules and JSX yet
+	fn can_format(&self) -> bool {
+		!self.source_type.module && !self.source_type.jsx
+	}
+}
+
+impl Default for JsFileHandler {
+	fn default() -> Self {
+		Self::script()
+	}
+}
 
 impl ExtensionHandler for JsFileHandler {
 	fn capabilities(&self) -> super::Capabilities {
 		super::Capabilities {
-			format: true,
+			format: self.can_format(),
 			lint: true,
 		}
 	}
@@ -15,10 +68,39 @@ impl ExtensionHandler for JsFileHandler {
 		super::Language::Js
 	}
 
+	fn source_type(&self) -> SourceType {
+		self.source_type
+	}
+
 	fn mime(&self) -> super::Mime {
 		Mime::Javascript
 	}
 
+	fn parse(&self, text: &str, file_id: FileId) -> Option<Parse> {
+		let syntax = if self.source_type.module {
+			Syntax::default().module()
+		} else {
+			Syntax::default().script()
+		};
+		Some(parse_with_syntax(text, file_id, syntax).into())
+	}
+
+	fn format(&self, text: &str, options: FormatOptions) -> FormatResult<Formatted> {
+		if !self.can_format() {
+			return Err(FormatError::UnsupportedLanguage);
+		}
+		let parse = parse_with_syntax(text, 0, Syntax::default().script());
+		Formatter::new(options).format_root(&parse.syntax())
+	}
+
+	fn extensions(&self) -> &[&'static str] {
+		self.extensions
+	}
+
+	fn interpreters(&self) -> &[&'static str] {
+		self.interpreters
+	}
+
 	fn may_use_tabs(&self) -> bool {
 		true
 	}
@@ -1,11 +1,51 @@
-use super::{ExtensionHandler, Mime};
+use super::{ExtensionHandler, Mime, SourceType};
+use rome_formatter::{
+	format_element, tokenize_json, FormatError, FormatOptions, FormatResult, Formatted,
+};
+
 #[derive(Debug, PartialEq, Eq)]
-pub struct JsonFileHandler {}
+pub struct JsonFileHandler {
+	extensions: &'static [&'static str],
+	file_names: &'static [&'static str],
+	source_type: SourceType,
+}
+
+impl JsonFileHandler {
+	/// The `.json` files
+	pub fn json() -> Self {
+		Self {
+			extensions: &["json"],
+			file_names: &[],
+			source_type: SourceType::default(),
+		}
+	}
+
+	/// The files that can contain comments: the `.jsonc` files and the configuration files
+	/// of TypeScript and Rome
+	pub fn jsonc() -> Self {
+		Self {
+			extensions: &["jsonc"],
+			file_names: &["tsconfig.json", "jsconfig.json", "rome.json"],
+			source_type: SourceType {
+				comments: true,
+				..SourceType::default()
+			},
+		}
+	}
+}
+
+impl Default for JsonFileHandler {
+	fn default() -> Self {
+		Self::json()
+	}
+}
 
 impl ExtensionHandler for JsonFileHandler {
 	fn capabilities(&self) -> super::Capabilities {
+		// the formatter of JSON drops the comments, the files that can contain comments
+		// aren't formatted
 		super::Capabilities {
-			format: true,
+			format: !self.source_type.comments,
 			lint: true,
 		}
 	}
@@ -14,10 +54,30 @@ impl ExtensionHandler for JsonFileHandler {
 		super::Language::Json
 	}
 
+	fn source_type(&self) -> SourceType {
+		self.source_type
+	}
+
 	fn mime(&self) -> super::Mime {
 		Mime::Json
 	}
 
+	fn format(&self, text: &str, options: FormatOptions) -> FormatResult<Formatted> {
+		if self.source_type.comments {
+			return Err(FormatError::UnsupportedLanguage);
+		}
+		let element = tokenize_json(text);
+		Ok(format_element(&element, options))
+	}
+
+	fn extensions(&self) -> &[&'static str] {
+		self.extensions
+	}
+
+	fn file_names(&self) -> &[&'static str] {
+		self.file_names
+	}
+
 	fn may_use_tabs(&self) -> bool {
 		true
 	}
@@ -1,7 +1,13 @@
+use rome_formatter::{FormatError, FormatOptions, FormatResult, Formatted};
+use rslint_errors::{file::FileId, Diagnostic};
+use rslint_parser::SyntaxNode;
+
 pub mod javascript;
 pub mod json;
+pub mod typescript;
 pub mod unknown;
 
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum Language {
 	Js,
 	Json,
@@ -9,8 +15,23 @@ pub enum Language {
 	Unknown,
 }
 
+/// The flavour of a language used by the files of a handler, it tells the parser which
+/// syntax the files can contain
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub struct SourceType {
+	/// The files are ECMAScript modules, like the `.mjs` files, rather than scripts
+	pub module: bool,
+	/// The files can contain JSX elements, like the `.jsx` and `.tsx` files
+	pub jsx: bool,
+	/// The files only contain declarations of types, like the `.d.ts` files
+	pub declaration: bool,
+	/// The files can contain comments, like the `.jsonc` files
+	pub comments: bool,
+}
+
 pub enum Mime {
 	Javascript,
+	Typescript,
 	Json,
 	Css,
 	Text,
@@ -22,11 +43,28 @@ impl std::fmt::Display for Mime {
 			Mime::Css => write!(f, "text/css"),
 			Mime::Json => write!(f, "application/json"),
 			Mime::Javascript => write!(f, "application/javascript"),
+			Mime::Typescript => write!(f, "application/typescript"),
 			Mime::Text => write!(f, "text/plain"),
 		}
 	}
 }
 
+/// The syntax tree of a file, along with the errors found by the parser
+#[derive(Debug, Clone)]
+pub struct Parse {
+	pub root: SyntaxNode,
+	pub diagnostics: Vec<Diagnostic>,
+}
+
+impl<T> From<rslint_parser::Parse<T>> for Parse {
+	fn from(parse: rslint_parser::Parse<T>) -> Self {
+		Self {
+			root: parse.syntax(),
+			diagnostics: parse.errors().to_vec(),
+		}
+	}
+}
+
 #[derive(Debug)]
 pub struct Capabilities {
 	pub lint: bool,
@@ -34,14 +72,38 @@ pub struct Capabilities {
 }
 
 /// Main trait to use to add a new language to Rome
-pub trait ExtensionHandler {
+///
+/// Handlers are shared between the threads that process the files, hence they must be [Send] and [Sync]
+pub trait ExtensionHandler: Send + Sync {
 	/// The language of the file. It can be a super language.
 	/// For example, a ".js" file can have [Language::Ts]
 	fn language(&self) -> Language;
 
+	/// The flavour of the language used by the files
+	fn source_type(&self) -> SourceType {
+		SourceType::default()
+	}
+
 	/// MIME types used to identify a certain language
 	fn mime(&self) -> Mime;
 
+	/// The extensions of the files, without the leading dot. An extension can have multiple
+	/// parts, like `d.ts`, the longest extension of a file is preferred
+	fn extensions(&self) -> &[&'static str] {
+		&[]
+	}
+
+	/// The exact names of the files, like `tsconfig.json`. They are preferred to the extensions
+	fn file_names(&self) -> &[&'static str] {
+		&[]
+	}
+
+	/// The interpreters named by the shebang of the files, like `node` for the files
+	/// starting with `#!/usr/bin/env node`
+	fn interpreters(&self) -> &[&'static str] {
+		&[]
+	}
+
 	/// A file that can support tabs inside its content
 	fn may_use_tabs(&self) -> bool {
 		true
@@ -55,6 +117,20 @@ pub trait ExtensionHandler {
 		}
 	}
 
+	/// Parses the content of a file, that doesn't start with a byte order mark.
+	///
+	/// Returns [None] when the language doesn't have a parser
+	fn parse(&self, _text: &str, _file_id: FileId) -> Option<Parse> {
+		None
+	}
+
+	/// Formats the content of a file, that doesn't start with a byte order mark.
+	///
+	/// Returns [FormatError::UnsupportedLanguage] when the files of the handler can't be formatted
+	fn format(&self, _text: &str, _options: FormatOptions) -> FormatResult<Formatted> {
+		Err(FormatError::UnsupportedLanguage)
+	}
+
 	/// How a file should be treated. Usually an asset doesn't posses a parser.
 	///
 	/// An image should me parked as asset.
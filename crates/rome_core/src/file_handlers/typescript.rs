@@ -0,0 +1,109 @@
+use super::{ExtensionHandler, Mime, Parse, SourceType};
+use rslint_errors::file::FileId;
+use rslint_parser::{parse_with_syntax, Syntax};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct TsFileHandler {
+	extensions: &'static [&'static str],
+	interpreters: &'static [&'static str],
+	source_type: SourceType,
+}
+
+impl TsFileHandler {
+	/// The `.ts` and `.cts` files, and the files run by ts-node and Deno
+	pub fn script() -> Self {
+		Self {
+			extensions: &["ts", "cts"],
+			interpreters: &["ts-node", "deno"],
+			source_type: SourceType::default(),
+		}
+	}
+
+	/// The ECMAScript modules, the `.mts` files
+	pub fn module() -> Self {
+		Self {
+			extensions: &["mts"],
+			interpreters: &[],
+			source_type: SourceType {
+				module: true,
+				..SourceType::default()
+			},
+		}
+	}
+
+	/// The `.tsx` files
+	pub fn jsx() -> Self {
+		Self {
+			extensions: &["tsx"],
+			interpreters: &[],
+			source_type: SourceType {
+				jsx: true,
+				..SourceType::default()
+			},
+		}
+	}
+
+	/// The files that only declare types, the `.d.ts` files
+	pub fn declaration() -> Self {
+		Self {
+			extensions: &["d.ts", "d.mts", "d.cts"],
+			interpreters: &[],
+			source_type: SourceType {
+				declaration: true,
+				..SourceType::default()
+			},
+		}
+	}
+}
+
+impl Default for TsFileHandler {
+	fn default() -> Self {
+		Self::script()
+	}
+}
+
+impl ExtensionHandler for TsFileHandler {
+	fn capabilities(&self) -> super::Capabilities {
+		// the formatter doesn't support the syntax of TypeScript yet, while the rules work on
+		// the syntax tree of any file
+		super::Capabilities {
+			format: false,
+			lint: true,
+		}
+	}
+
+	fn language(&self) -> super::Language {
+		super::Language::Ts
+	}
+
+	fn source_type(&self) -> SourceType {
+		self.source_type
+	}
+
+	fn mime(&self) -> super::Mime {
+		Mime::Typescript
+	}
+
+	fn parse(&self, text: &str, file_id: FileId) -> Option<Parse> {
+		// the parser doesn't support JSX yet, and it reads the declarations of types in any
+		// TypeScript file, so only the modules need their own syntax
+		let syntax = if self.source_type.module {
+			Syntax::default().typescript_module()
+		} else {
+			Syntax::default().typescript()
+		};
+		Some(parse_with_syntax(text, file_id, syntax).into())
+	}
+
+	fn extensions(&self) -> &[&'static str] {
+		self.extensions
+	}
+
+	fn interpreters(&self) -> &[&'static str] {
+		self.interpreters
+	}
+
+	fn may_use_tabs(&self) -> bool {
+		true
+	}
+}
@@ -1,45 +1,370 @@
-use crate::file_handlers::{javascript::JsFileHandler, unknown::UnknownFileHandler};
-use file_handlers::{json::JsonFileHandler, ExtensionHandler};
+use crate::file_handlers::{
+	javascript::JsFileHandler, json::JsonFileHandler, typescript::TsFileHandler,
+	unknown::UnknownFileHandler, ExtensionHandler,
+};
 use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::path::Path;
 
 pub mod file_handlers;
+mod sniffing;
+mod workspace;
 
-// these strings will live for the whole App, so it makes sense to have them as static
-pub type Handlers = HashMap<&'static str, Box<dyn ExtensionHandler>>;
+pub use workspace::Workspace;
 
+/// What a handler claims, to be chosen for a file
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Claim {
+	Extension,
+	FileName,
+	Interpreter,
+}
+
+impl fmt::Display for Claim {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Claim::Extension => fmt.write_str("extension"),
+			Claim::FileName => fmt.write_str("file name"),
+			Claim::Interpreter => fmt.write_str("interpreter"),
+		}
+	}
+}
+
+/// A handler can't be registered, as something it claims is already claimed
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RegistrationError {
+	pub claim: Claim,
+	pub name: &'static str,
+}
+
+impl fmt::Display for RegistrationError {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			fmt,
+			"the {} \"{}\" is already claimed by another handler",
+			self.claim, self.name
+		)
+	}
+}
+
+impl Error for RegistrationError {}
+
+/// The registry of the handlers, that finds the handler of a file from its name or its shebang
 pub struct App {
-	handlers: Handlers,
+	handlers: Vec<Box<dyn ExtensionHandler>>,
+	// these strings will live for the whole App, so it makes sense to have them as static.
+	// The values are indexes in `handlers`
+	extensions: HashMap<&'static str, usize>,
+	file_names: HashMap<&'static str, usize>,
+	interpreters: HashMap<&'static str, usize>,
 	unknown_handler: Box<dyn ExtensionHandler>,
 }
 
 impl Default for App {
 	fn default() -> Self {
-		let mut map: Handlers = HashMap::new();
-		map.insert("js", Box::new(JsFileHandler {}));
-		map.insert("ts", Box::new(JsFileHandler {}));
-		map.insert("json", Box::new(JsonFileHandler {}));
-		Self {
-			handlers: map,
-			unknown_handler: Box::new(UnknownFileHandler {}),
+		let mut app = Self::empty();
+		let handlers: [Box<dyn ExtensionHandler>; 9] = [
+			Box::new(JsFileHandler::script()),
+			Box::new(JsFileHandler::module()),
+			Box::new(JsFileHandler::jsx()),
+			Box::new(TsFileHandler::script()),
+			Box::new(TsFileHandler::module()),
+			Box::new(TsFileHandler::jsx()),
+			Box::new(TsFileHandler::declaration()),
+			Box::new(JsonFileHandler::json()),
+			Box::new(JsonFileHandler::jsonc()),
+		];
+		for handler in handlers {
+			app.register(handler)
+				.expect("the default handlers don't conflict");
 		}
+		app
 	}
 }
 
 impl App {
+	/// Creates an app with the handlers of the languages supported by Rome
 	pub fn new() -> Self {
 		Default::default()
 	}
 
-	pub fn get_handler<'a>(&self, file_extension: &'a str) -> Option<&dyn ExtensionHandler> {
-		let handler = if self.handlers.contains_key(file_extension) {
-			self.handlers.get(file_extension)
+	/// Creates an app without handlers, every file gets the unknown handler
+	pub fn empty() -> Self {
+		Self {
+			handlers: Vec::new(),
+			extensions: HashMap::new(),
+			file_names: HashMap::new(),
+			interpreters: HashMap::new(),
+			unknown_handler: Box::new(UnknownFileHandler {}),
+		}
+	}
+
+	/// Adds a handler, that will be chosen for the files with the extensions, the names and the
+	/// interpreters it claims.
+	///
+	/// The handler isn't added when any of them is already claimed by another handler
+	pub fn register(
+		&mut self,
+		handler: Box<dyn ExtensionHandler>,
+	) -> Result<(), RegistrationError> {
+		let claims = [
+			(Claim::Extension, &self.extensions, handler.extensions()),
+			(Claim::FileName, &self.file_names, handler.file_names()),
+			(
+				Claim::Interpreter,
+				&self.interpreters,
+				handler.interpreters(),
+			),
+		];
+		for (claim, claimed, names) in claims.iter() {
+			for (index, &name) in names.iter().enumerate() {
+				// the handler may also claim a name twice
+				if claimed.contains_key(name) || names[..index].contains(&name) {
+					return Err(RegistrationError {
+						claim: *claim,
+						name,
+					});
+				}
+			}
+		}
+
+		let index = self.handlers.len();
+		for extension in handler.extensions() {
+			self.extensions.insert(extension, index);
+		}
+		for file_name in handler.file_names() {
+			self.file_names.insert(file_name, index);
+		}
+		for interpreter in handler.interpreters() {
+			self.interpreters.insert(interpreter, index);
+		}
+		self.handlers.push(handler);
+		Ok(())
+	}
+
+	/// The handler of the files that no handler claims
+	pub fn unknown_handler(&self) -> &dyn ExtensionHandler {
+		self.unknown_handler.as_ref()
+	}
+
+	/// Finds the handler of the file from its name, or from its longest extension: a `.d.ts`
+	/// file gets the handler of `d.ts` before the one of `ts`. The extensions are compared
+	/// without case
+	pub fn handler_for_path(&self, path: &Path) -> Option<&dyn ExtensionHandler> {
+		let file_name = path.file_name()?.to_str()?;
+		if let Some(&index) = self.file_names.get(file_name) {
+			return Some(self.handlers[index].as_ref());
+		}
+
+		// the leading dot of a hidden file doesn't start an extension
+		let file_name = file_name.to_lowercase();
+		let start = usize::from(file_name.starts_with('.'));
+		file_name[start..]
+			.match_indices('.')
+			.find_map(|(dot, _)| self.handler_for_extension(&file_name[start + dot + 1..]))
+	}
+
+	/// Finds the handler of a file from its path, like [App::handler_for_path]. The language of
+	/// the files without extension is guessed from their content, see [App::handler_for_content]
+	pub fn handler_for_file(&self, path: &Path, content: &str) -> Option<&dyn ExtensionHandler> {
+		self.handler_for_path(path).or_else(|| {
+			if path.extension().is_none() {
+				self.handler_for_content(content)
+			} else {
+				None
+			}
+		})
+	}
+
+	pub fn handler_for_extension(&self, extension: &str) -> Option<&dyn ExtensionHandler> {
+		self.extensions
+			.get(extension)
+			.map(|&index| self.handlers[index].as_ref())
+	}
+
+	/// Finds the handler of a file from its first line, when it's a shebang like
+	/// `#!/usr/bin/env node`
+	pub fn handler_for_shebang(&self, first_line: &str) -> Option<&dyn ExtensionHandler> {
+		let interpreter = shebang_interpreter(first_line)?;
+		self.interpreters
+			.get(interpreter)
+			.map(|&index| self.handlers[index].as_ref())
+	}
+
+	/// Finds the handler of a file from its content, for the files whose name doesn't tell
+	/// their language. In order, the content is checked for:
+	/// - a shebang, which is the only hint used when it's there
+	/// - a modeline of Vim or Emacs, like `// vim: set ft=javascript:`
+	/// - a JSON object or array
+	/// - a script starting with the `"use strict"` directive
+	pub fn handler_for_content(&self, content: &str) -> Option<&dyn ExtensionHandler> {
+		let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+		let first_line = content.lines().next()?;
+		if first_line.starts_with("#!") {
+			return self.handler_for_shebang(first_line);
+		}
+
+		if let Some(language) = sniffing::modeline_language(content) {
+			return sniffing::language_extension(language)
+				.and_then(|extension| self.handler_for_extension(extension));
+		}
+		if sniffing::is_json(content) {
+			self.handler_for_extension("json")
+		} else if sniffing::is_strict_script(content) {
+			self.handler_for_extension("js")
 		} else {
-			Some(&self.unknown_handler)
-		};
-		handler.map(|handler| handler.as_ref())
+			None
+		}
 	}
 }
 
+/// The name of the program that runs the file, from its shebang. The program can be run through
+/// `env`, which can receive options and variables before the name of the program
+fn shebang_interpreter(line: &str) -> Option<&str> {
+	let mut words = line.strip_prefix("#!")?.split_whitespace();
+	let program = file_name(words.next()?);
+	if program == "env" {
+		words
+			.find(|word| !word.starts_with('-') && !word.contains('='))
+			.map(file_name)
+	} else {
+		Some(program)
+	}
+}
+
+fn file_name(path: &str) -> &str {
+	path.rsplit('/').next().unwrap_or(path)
+}
+
 pub fn create_app() -> App {
 	App::new()
 }
+
+#[cfg(test)]
+mod test {
+	use crate::file_handlers::{javascript::JsFileHandler, Language, SourceType};
+	use crate::{App, Claim, RegistrationError};
+	use std::path::Path;
+
+	fn language_of(app: &App, path: &str) -> Option<(Language, SourceType)> {
+		app.handler_for_path(Path::new(path))
+			.map(|handler| (handler.language(), handler.source_type()))
+	}
+
+	#[test]
+	fn finds_handlers() {
+		let app = App::new();
+		let module = SourceType {
+			module: true,
+			..SourceType::default()
+		};
+
+		assert_eq!(
+			language_of(&app, "src/a.js"),
+			Some((Language::Js, SourceType::default()))
+		);
+		assert_eq!(language_of(&app, "a.MJS"), Some((Language::Js, module)));
+		assert_eq!(language_of(&app, "a.mts"), Some((Language::Ts, module)));
+		assert_eq!(
+			language_of(&app, "types/a.d.ts").map(|(_, source_type)| source_type.declaration),
+			Some(true)
+		);
+		assert_eq!(
+			language_of(&app, "a.test.tsx").map(|(_, source_type)| source_type.jsx),
+			Some(true)
+		);
+		assert_eq!(
+			language_of(&app, "tsconfig.json").map(|(_, source_type)| source_type.comments),
+			Some(true)
+		);
+		assert_eq!(
+			language_of(&app, "rome.json").map(|(_, source_type)| source_type.comments),
+			Some(true)
+		);
+		assert_eq!(language_of(&app, ".js"), None);
+		assert_eq!(language_of(&app, "README"), None);
+		assert_eq!(language_of(&app, "a.rs"), None);
+	}
+
+	#[test]
+	fn finds_handlers_from_shebang() {
+		let app = App::new();
+		let language_of = |line| {
+			app.handler_for_shebang(line)
+				.map(|handler| handler.language())
+		};
+
+		assert_eq!(language_of("#!/usr/bin/env node"), Some(Language::Js));
+		assert_eq!(
+			language_of("#!/usr/local/bin/node --harmony"),
+			Some(Language::Js)
+		);
+		assert_eq!(
+			language_of("#!/usr/bin/env -S deno run"),
+			Some(Language::Ts)
+		);
+		assert_eq!(language_of("#!/bin/sh"), None);
+		assert_eq!(language_of("let a = 1;"), None);
+	}
+
+	#[test]
+	fn finds_handlers_from_content() {
+		let app = App::new();
+		let language_of = |content| {
+			app.handler_for_content(content)
+				.map(|handler| (handler.language(), handler.source_type()))
+		};
+		let script = SourceType::default();
+
+		assert_eq!(
+			language_of("#!/usr/bin/env bun\nconsole.log(1);"),
+			Some((Language::Js, script))
+		);
+		assert_eq!(
+			language_of("\u{feff}#!/usr/bin/env ts-node\n"),
+			Some((Language::Ts, script))
+		);
+		// the shell scripts aren't guessed from the rest of their content
+		assert_eq!(language_of("#!/bin/sh\n# vim: ft=javascript"), None);
+		assert_eq!(
+			language_of("// vim: set ft=javascriptreact :\nlet a = <div />;"),
+			Some((
+				Language::Js,
+				SourceType {
+					jsx: true,
+					..SourceType::default()
+				}
+			))
+		);
+		assert_eq!(language_of("# -*- mode: python -*-\n{}"), None);
+		assert_eq!(
+			language_of("{\n  \"name\": \"rome\"\n}\n"),
+			Some((Language::Json, script))
+		);
+		assert_eq!(
+			language_of("/* a script */\n\"use strict\";"),
+			Some((Language::Js, script))
+		);
+		assert_eq!(language_of("{ let a = 1; }"), None);
+		assert_eq!(language_of("Some notes"), None);
+		assert_eq!(language_of(""), None);
+	}
+
+	#[test]
+	fn detects_conflicts() {
+		let mut app = App::empty();
+		app.register(Box::new(JsFileHandler::script())).unwrap();
+
+		assert_eq!(
+			app.register(Box::new(JsFileHandler::script())),
+			Err(RegistrationError {
+				claim: Claim::Extension,
+				name: "js"
+			})
+		);
+		// the handler that conflicts isn't registered
+		assert_eq!(app.handlers.len(), 1);
+		app.register(Box::new(JsFileHandler::module())).unwrap();
+	}
+}
@@ -0,0 +1,152 @@
+//! Guesses the language of a file from its content, for the files whose name doesn't tell it
+
+/// The number of lines, at the start and at the end of a file, that can contain a modeline.
+/// It's the default of Vim
+const MODELINE_LINES: usize = 5;
+
+/// The language named by a modeline of Vim, like `vim: set ft=javascript:`, or of Emacs,
+/// like `-*- mode: js -*-`
+pub(crate) fn modeline_language(content: &str) -> Option<&str> {
+	content
+		.lines()
+		.take(MODELINE_LINES)
+		.chain(content.lines().rev().take(MODELINE_LINES))
+		.find_map(|line| vim_file_type(line).or_else(|| emacs_mode(line)))
+}
+
+/// The extension of the files of a language, named as Vim or Emacs name it
+pub(crate) fn language_extension(language: &str) -> Option<&'static str> {
+	let language = language.to_ascii_lowercase();
+	match language.strip_suffix("-mode").unwrap_or(&language) {
+		"javascript" | "js" | "js2" | "js3" | "node" => Some("js"),
+		"javascriptreact" | "jsx" | "rjsx" => Some("jsx"),
+		"typescript" | "ts" => Some("ts"),
+		"typescriptreact" | "tsx" => Some("tsx"),
+		"json" => Some("json"),
+		"jsonc" => Some("jsonc"),
+		_ => None,
+	}
+}
+
+/// The file type set by a modeline of Vim, the marker must start the line or follow a blank
+fn vim_file_type(line: &str) -> Option<&str> {
+	let options = ["vim:", "vi:", "ex:"].iter().find_map(|marker| {
+		line.match_indices(marker)
+			.find(|(index, _)| {
+				matches!(
+					line[..*index].chars().next_back(),
+					None | Some(' ') | Some('\t')
+				)
+			})
+			.map(|(index, _)| &line[index + marker.len()..])
+	})?;
+
+	options
+		.split(|c: char| c.is_whitespace() || c == ':')
+		.find_map(|option| {
+			let (name, value) = option.split_once('=')?;
+			match name {
+				"ft" | "filetype" | "syn" | "syntax" if !value.is_empty() => Some(value),
+				_ => None,
+			}
+		})
+}
+
+/// The major mode set by a modeline of Emacs, either `-*- js -*-` or a list of variables
+/// like `-*- mode: js; indent-tabs-mode: nil -*-`
+fn emacs_mode(line: &str) -> Option<&str> {
+	let (_, rest) = line.split_once("-*-")?;
+	let (variables, _) = rest.split_once("-*-")?;
+
+	if !variables.contains(':') {
+		let mode = variables.trim();
+		return if mode.is_empty() { None } else { Some(mode) };
+	}
+	variables.split(';').find_map(|variable| {
+		let (name, value) = variable.split_once(':')?;
+		if name.trim().eq_ignore_ascii_case("mode") {
+			Some(value.trim())
+		} else {
+			None
+		}
+	})
+}
+
+/// A file that is a JSON object or array. A block of JavaScript can also start with a brace,
+/// hence the content must be valid JSON
+pub(crate) fn is_json(content: &str) -> bool {
+	let content = content.trim_start();
+	(content.starts_with('{') || content.starts_with('['))
+		&& serde_json::from_str::<serde_json::Value>(content).is_ok()
+}
+
+/// A script that starts with the `"use strict"` directive, after its comments
+pub(crate) fn is_strict_script(content: &str) -> bool {
+	let mut rest = content.trim_start();
+	loop {
+		if let Some(comment) = rest.strip_prefix("//") {
+			rest = comment.split_once('\n').map_or("", |(_, rest)| rest);
+		} else if let Some(comment) = rest.strip_prefix("/*") {
+			match comment.split_once("*/") {
+				Some((_, rest_of_file)) => rest = rest_of_file,
+				None => return false,
+			}
+		} else {
+			break;
+		}
+		rest = rest.trim_start();
+	}
+
+	rest.starts_with("\"use strict\"") || rest.starts_with("'use strict'")
+}
+
+#[cfg(test)]
+mod test {
+	use super::{is_json, is_strict_script, language_extension, modeline_language};
+
+	#[test]
+	fn finds_modelines() {
+		assert_eq!(
+			modeline_language("// vim: set ft=javascript :\n"),
+			Some("javascript")
+		);
+		assert_eq!(
+			modeline_language("/* vi:ts=2:filetype=json */"),
+			Some("json")
+		);
+		assert_eq!(
+			modeline_language("// -*- mode: js2; tab-width: 2 -*-"),
+			Some("js2")
+		);
+		assert_eq!(
+			modeline_language("#!/bin/sh\n# -*- typescript -*-"),
+			Some("typescript")
+		);
+		assert_eq!(
+			modeline_language("let a = 1;\n\n\n\n\n\n// vim: ft=typescriptreact"),
+			Some("typescriptreact")
+		);
+		// the marker must be a word of its own
+		assert_eq!(modeline_language("let index: ft=js"), None);
+		assert_eq!(modeline_language("// vim: ts=2"), None);
+
+		assert_eq!(language_extension("JavaScript"), Some("js"));
+		assert_eq!(language_extension("js2-mode"), Some("js"));
+		assert_eq!(language_extension("python"), None);
+	}
+
+	#[test]
+	fn detects_json_and_scripts() {
+		assert!(is_json("\n{ \"name\": \"rome\" }\n"));
+		assert!(is_json("[1, 2]"));
+		assert!(!is_json("{ let a = 1; }"));
+		assert!(!is_json("\"a string\""));
+
+		assert!(is_strict_script("'use strict';\nlet a = 1;"));
+		assert!(is_strict_script(
+			"// a script\n/* with comments */\n\"use strict\""
+		));
+		assert!(!is_strict_script("let a = 'use strict';"));
+		assert!(!is_strict_script("/* \"use strict\""));
+	}
+}
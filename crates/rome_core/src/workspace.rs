@@ -0,0 +1,161 @@
+use crate::file_handlers::Parse;
+use crate::App;
+use rslint_errors::file::{FileId, Files, SimpleFile};
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// The files processed by Rome, along with their source.
+///
+/// Each file gets a [FileId] the first time it's added, the same id is kept when its source is
+/// replaced. The id is passed to the parser, so the diagnostics of any file of the workspace can
+/// be emitted with their name and their lines, as the workspace implements [Files]
+#[derive(Debug, Default)]
+pub struct Workspace {
+	/// The index of a file is its id
+	files: Vec<WorkspaceFile>,
+	ids: HashMap<PathBuf, FileId>,
+}
+
+#[derive(Debug)]
+struct WorkspaceFile {
+	path: PathBuf,
+	file: SimpleFile,
+}
+
+impl Workspace {
+	pub fn new() -> Self {
+		Default::default()
+	}
+
+	/// Adds the file at `path` with its source, or replaces the source when the file is already
+	/// in the workspace. Returns the id of the file
+	pub fn insert(&mut self, path: &Path, source: impl Into<String>) -> FileId {
+		let file = SimpleFile::new(path.display().to_string(), source.into());
+		match self.ids.get(path) {
+			Some(&id) => {
+				self.files[id].file = file;
+				id
+			}
+			None => {
+				let id = self.files.len();
+				self.files.push(WorkspaceFile {
+					path: path.to_path_buf(),
+					file,
+				});
+				self.ids.insert(path.to_path_buf(), id);
+				id
+			}
+		}
+	}
+
+	/// The id of the file at `path`, if it's in the workspace
+	pub fn file_id(&self, path: &Path) -> Option<FileId> {
+		self.ids.get(path).copied()
+	}
+
+	/// The path of the file identified by `id`
+	pub fn path(&self, id: FileId) -> Option<&Path> {
+		self.files.get(id).map(|file| file.path.as_path())
+	}
+
+	/// The number of files in the workspace
+	pub fn len(&self) -> usize {
+		self.files.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.files.is_empty()
+	}
+
+	/// Parses the file identified by `id` with the handler of its language, see
+	/// [App::handler_for_file]. The diagnostics of the parser point to the file.
+	///
+	/// Returns [None] when the file isn't in the workspace or its language doesn't have a parser
+	pub fn parse(&self, app: &App, id: FileId) -> Option<Parse> {
+		let file = self.files.get(id)?;
+		let source = file.file.source(id)?;
+		app.handler_for_file(&file.path, source)?.parse(source, id)
+	}
+}
+
+impl Files for Workspace {
+	fn name(&self, id: FileId) -> Option<&str> {
+		self.files.get(id)?.file.name(id)
+	}
+
+	fn source(&self, id: FileId) -> Option<&str> {
+		self.files.get(id)?.file.source(id)
+	}
+
+	fn line_index(&self, id: FileId, byte_index: usize) -> Option<usize> {
+		self.files.get(id)?.file.line_index(id, byte_index)
+	}
+
+	fn line_range(&self, id: FileId, line_index: usize) -> Option<Range<usize>> {
+		self.files.get(id)?.file.line_range(id, line_index)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::{App, Workspace};
+	use rslint_errors::file::Files;
+	use std::path::Path;
+
+	#[test]
+	fn keeps_file_ids() {
+		let mut workspace = Workspace::new();
+		let index = workspace.insert(Path::new("src/index.js"), "let a = 1;");
+		let config = workspace.insert(Path::new("rome.json"), "{}");
+		assert_ne!(index, config);
+
+		assert_eq!(
+			workspace.insert(Path::new("src/index.js"), "let a = 1;\nlet b = 2;"),
+			index
+		);
+		assert_eq!(workspace.len(), 2);
+		assert_eq!(workspace.file_id(Path::new("rome.json")), Some(config));
+		assert_eq!(workspace.path(index), Some(Path::new("src/index.js")));
+		assert_eq!(workspace.name(index), Some("src/index.js"));
+		assert_eq!(workspace.line_index(index, 12), Some(1));
+		assert_eq!(workspace.line_range(index, 1), Some(11..21));
+		assert_eq!(workspace.source(2), None);
+	}
+
+	#[test]
+	fn parses_files() {
+		let app = App::new();
+		let mut workspace = Workspace::new();
+		workspace.insert(Path::new("src/index.js"), "let a = 1;");
+		let broken = workspace.insert(Path::new("bin/deploy"), "#!/usr/bin/env node\nlet = ;");
+		let notes = workspace.insert(Path::new("notes.txt"), "Some notes");
+
+		let parse = workspace.parse(&app, broken).unwrap();
+		assert!(!parse.diagnostics.is_empty());
+		assert!(parse
+			.diagnostics
+			.iter()
+			.all(|diagnostic| diagnostic.file_id == broken));
+		assert!(workspace.parse(&app, notes).is_none());
+	}
+
+	#[test]
+	fn parses_typescript_modules() {
+		let app = App::new();
+		let mut workspace = Workspace::new();
+		let source = "import a from \"a\";\nexport const b: number = a;\n";
+		let module = workspace.insert(Path::new("src/index.mts"), source);
+		let parse = workspace.parse(&app, module).unwrap();
+		assert!(parse.diagnostics.is_empty());
+
+		// the modules are in strict mode, unlike the scripts
+		let source = "with (a) {}\n";
+		let module = workspace.insert(Path::new("src/strict.mts"), source);
+		let parse = workspace.parse(&app, module).unwrap();
+		assert!(!parse.diagnostics.is_empty());
+		let script = workspace.insert(Path::new("src/sloppy.ts"), source);
+		let parse = workspace.parse(&app, script).unwrap();
+		assert!(parse.diagnostics.is_empty());
+	}
+}
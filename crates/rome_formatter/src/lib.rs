@@ -56,7 +56,7 @@ mod intersperse;
 mod printer;
 mod ts;
 
-use crate::format_json::tokenize_json;
+pub use crate::format_json::tokenize_json;
 
 pub use formatter::Formatter;
 use rslint_parser::SyntaxError;
@@ -66,14 +66,11 @@ pub use format_element::{
 	if_group_fits_on_single_line, indent, join_elements, soft_indent, soft_line_break,
 	soft_line_break_or_space, space_token, token, FormatElement,
 };
+pub use printer::LineEnding;
 pub use printer::Printer;
 pub use printer::PrinterOptions;
-use rome_core::file_handlers::Language;
-use rome_core::App;
-use rome_path::RomePath;
-use rslint_parser::parse_text;
 
-use std::io::Read;
+use std::fmt;
 use std::str::FromStr;
 
 /// This trait should be implemented on each node/value that should have a formatted representation
@@ -97,6 +94,18 @@ pub enum FormatError {
 	CapabilityDisabled,
 }
 
+impl fmt::Display for FormatError {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			FormatError::MissingRequiredChild => fmt.write_str("missing required child"),
+			FormatError::UnsupportedLanguage => fmt.write_str("language is not supported"),
+			FormatError::CapabilityDisabled => fmt.write_str("formatting capability is disabled"),
+		}
+	}
+}
+
+impl std::error::Error for FormatError {}
+
 impl From<SyntaxError> for FormatError {
 	fn from(syntax_error: SyntaxError) -> Self {
 		match syntax_error {
@@ -132,13 +141,103 @@ impl FromStr for IndentStyle {
 	}
 }
 
-#[derive(Debug)]
+/// The quotes used to delimit the string literals
+///
+/// ```rust
+/// use rome_formatter::{FormatOptions, Formatter, QuoteStyle};
+/// use rslint_parser::parse_text;
+///
+/// let options = FormatOptions {
+///     quote_style: QuoteStyle::Single,
+///     ..FormatOptions::default()
+/// };
+/// let parse = parse_text("let a = \"b\"", 0);
+/// let result = Formatter::new(options).format_root(&parse.syntax()).unwrap();
+/// assert_eq!(result.code(), "let a = 'b';\n");
+/// ```
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum QuoteStyle {
+	Double,
+	Single,
+}
+
+impl QuoteStyle {
+	/// The character that delimits the strings
+	pub const fn as_char(&self) -> char {
+		match self {
+			QuoteStyle::Double => '"',
+			QuoteStyle::Single => '\'',
+		}
+	}
+}
+
+impl Default for QuoteStyle {
+	fn default() -> Self {
+		Self::Double
+	}
+}
+
+impl FromStr for QuoteStyle {
+	type Err = &'static str;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"double" => Ok(Self::Double),
+			"single" => Ok(Self::Single),
+			_ => Err("Value not supported for QuoteStyle"),
+		}
+	}
+}
+
+/// What happens to the byte order mark at the start of a file.
+///
+/// The byte order mark is never parsed: it's removed before formatting the code, and added back
+/// to the formatted code when it's kept
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum ByteOrderMark {
+	/// The formatted code starts with a byte order mark only if the source has one
+	Keep,
+	/// The formatted code never starts with a byte order mark
+	Remove,
+}
+
+impl Default for ByteOrderMark {
+	fn default() -> Self {
+		Self::Keep
+	}
+}
+
+impl FromStr for ByteOrderMark {
+	type Err = &'static str;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"keep" => Ok(Self::Keep),
+			"remove" => Ok(Self::Remove),
+			_ => Err("Value not supported for ByteOrderMark"),
+		}
+	}
+}
+
+#[derive(Debug, Clone)]
 pub struct FormatOptions {
 	/// The indent style
 	pub indent_style: IndentStyle,
 
+	/// The width of a tab character. Defaults to 2
+	pub tab_width: u8,
+
 	/// What's the max width of a line. Defaults to 80
 	pub line_width: u16,
+
+	/// The quotes used by the string literals. Defaults to double quotes
+	pub quote_style: QuoteStyle,
+
+	/// The line ending of the formatted code. Defaults to `\n`
+	pub line_ending: LineEnding,
+
+	/// What happens to the byte order mark of the source. Defaults to keeping it
+	pub byte_order_mark: ByteOrderMark,
 }
 
 impl FormatOptions {
@@ -154,7 +253,11 @@ impl Default for FormatOptions {
 	fn default() -> Self {
 		Self {
 			indent_style: IndentStyle::default(),
+			tab_width: 2,
 			line_width: 80,
+			quote_style: QuoteStyle::default(),
+			line_ending: LineEnding::LineFeed,
+			byte_order_mark: ByteOrderMark::default(),
 		}
 	}
 }
@@ -176,54 +279,6 @@ impl Formatted {
 	}
 }
 
-// TODO: implement me + handle errors
-/// Main function
-pub fn format(rome_path: &mut RomePath, options: FormatOptions) -> FormatResult<Formatted> {
-	// we assume that file exists
-	let mut file = rome_path.open();
-	let mut buffer = String::new();
-	// we assume we have permissions
-	file.read_to_string(&mut buffer)
-		.expect("cannot read the file to format");
-
-	if let Some(handler) = rome_path.get_handler() {
-		if handler.capabilities().format {
-			let result = match handler.language() {
-				Language::Js => {
-					let parsed_result = parse_text(buffer.as_str(), 0);
-					Formatter::new(options).format_root(&parsed_result.syntax())
-				}
-				Language::Json => {
-					let element = tokenize_json(buffer.as_str());
-					Ok(format_element(&element, options))
-				}
-				Language::Ts | Language::Unknown => Err(FormatError::UnsupportedLanguage),
-			};
-
-			result
-		} else {
-			Err(FormatError::CapabilityDisabled)
-		}
-	} else {
-		Err(FormatError::UnsupportedLanguage)
-	}
-}
-
-pub fn format_file_and_save(rome_path: &mut RomePath, options: FormatOptions) {
-	let result = format(rome_path, options);
-	if let Ok(result) = result {
-		rome_path
-			.save(result.code())
-			.expect("Could not write the formatted code on file");
-	}
-}
-
-pub fn format_file(path_to_file: &str, options: FormatOptions, app: &App) -> Formatted {
-	let mut rome_path = RomePath::new(path_to_file).deduce_handler(app);
-	let element = format(&mut rome_path, options);
-	element.unwrap()
-}
-
 pub fn format_element(element: &FormatElement, options: FormatOptions) -> Formatted {
 	let printer = Printer::new(options);
 	printer.print(element)
@@ -1,5 +1,6 @@
 use crate::format_element::{ConditionalGroupContent, Group, GroupPrintMode, LineMode};
 use crate::{FormatElement, FormatOptions, Formatted, IndentStyle};
+use std::str::FromStr;
 
 /// Options that affect how the [Printer] prints the format tokens
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -24,7 +25,6 @@ pub struct PrinterOptions {
 impl From<FormatOptions> for PrinterOptions {
 	fn from(options: FormatOptions) -> Self {
 		let indent_string: String;
-		let tab_width = 2;
 
 		match options.indent_style {
 			IndentStyle::Tab => indent_string = String::from("\t"),
@@ -33,14 +33,13 @@ impl From<FormatOptions> for PrinterOptions {
 
 		PrinterOptions {
 			indent_string,
-			tab_width,
+			tab_width: options.tab_width,
 			print_width: options.line_width,
-			..PrinterOptions::default()
+			line_ending: options.line_ending,
 		}
 	}
 }
 
-#[allow(dead_code)]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum LineEnding {
 	///  Line Feed only (\n), common on Linux and macOS as well as inside git repos
@@ -64,6 +63,19 @@ impl LineEnding {
 	}
 }
 
+impl FromStr for LineEnding {
+	type Err = &'static str;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"lf" => Ok(Self::LineFeed),
+			"crlf" => Ok(Self::CarriageReturnLineFeed),
+			"cr" => Ok(Self::CarriageReturn),
+			_ => Err("Value not supported for LineEnding"),
+		}
+	}
+}
+
 impl Default for PrinterOptions {
 	fn default() -> Self {
 		PrinterOptions {
@@ -2,13 +2,16 @@ use crate::{token, FormatElement, FormatResult, Formatter, ToFormatElement};
 use rslint_parser::ast::String as JsString;
 
 impl ToFormatElement for JsString {
-	fn to_format_element(&self, _formatter: &Formatter) -> FormatResult<FormatElement> {
+	fn to_format_element(&self, formatter: &Formatter) -> FormatResult<FormatElement> {
 		let mut content = self.to_string();
+		let quote = formatter.options().quote_style.as_char();
 
-		// uses single quotes
-		if content.starts_with('\'') {
-			content.replace_range(0..1, "\"");
-			content.replace_range(content.len() - 1..content.len(), "\"");
+		// uses the other kind of quotes, which are replaced only when the string
+		// doesn't contain the preferred quote, as it would need to be escaped
+		if !content.starts_with(quote) && !content[1..content.len() - 1].contains(quote) {
+			let quote = quote.to_string();
+			content.replace_range(0..1, &quote);
+			content.replace_range(content.len() - 1..content.len(), &quote);
 		}
 
 		Ok(token(content.as_str()))
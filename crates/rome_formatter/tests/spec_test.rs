@@ -1,5 +1,5 @@
 use rome_core::create_app;
-use rome_formatter::{format_file, FormatOptions};
+use rome_formatter::FormatOptions;
 use std::fs;
 use std::path::Path;
 
@@ -16,7 +16,6 @@ use std::path::Path;
 /// * `null` -> input: `tests/specs/null.json`, expected output: `tests/specs/null.expected.json`
 pub fn run(spec_input_file: &str, expected_file: &str) {
 	let app = create_app();
-	let file_path = &spec_input_file;
 	let spec_input_file = Path::new(spec_input_file);
 	let expected_file = Path::new(expected_file);
 
@@ -32,7 +31,11 @@ pub fn run(spec_input_file: &str, expected_file: &str) {
 		expected_file.display(),
 	);
 
-	let result = format_file(file_path, FormatOptions::default(), &app);
+	let handler = app
+		.handler_for_path(spec_input_file)
+		.expect("the language of the input must be supported");
+	let input = fs::read_to_string(spec_input_file).unwrap();
+	let result = handler.format(&input, FormatOptions::default()).unwrap();
 	let expected_output = fs::read_to_string(expected_file).unwrap();
 
 	assert_eq!(&expected_output, result.code());
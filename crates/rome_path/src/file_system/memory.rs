@@ -0,0 +1,348 @@
+use super::{FileKind, FileSystem, Metadata, Walk, WalkOptions, WatchGuard, IGNORE_FILE_NAMES};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::Match;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::io;
+use std::ops::Bound;
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc::Sender;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A file system that holds the files in memory.
+///
+/// The directories aren't stored: a directory exists as long as it contains a file. The relative
+/// paths start at the root directory, and only the ignore files inside the walked directory are
+/// respected.
+///
+/// ```rust
+/// use rome_path::{FileSystem, MemoryFileSystem};
+/// use std::path::Path;
+///
+/// let fs = MemoryFileSystem::default().with_file("src/index.js", "let a = 1;");
+/// assert_eq!(fs.read(Path::new("/src/index.js")).unwrap(), b"let a = 1;");
+/// assert!(fs.metadata(Path::new("src")).unwrap().is_dir());
+/// ```
+#[derive(Debug, Default)]
+pub struct MemoryFileSystem {
+	state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+	/// The files, by their normalized path
+	files: BTreeMap<PathBuf, MemoryFile>,
+	/// The watched directories, with the channel where their changes are sent
+	watchers: Vec<(Vec<PathBuf>, Sender<PathBuf>)>,
+	/// The number of writes, it gives a distinct modification time to each write
+	writes: u64,
+}
+
+#[derive(Debug)]
+struct MemoryFile {
+	content: Vec<u8>,
+	modified: SystemTime,
+}
+
+impl MemoryFileSystem {
+	/// Adds a file, replacing the one that has the same path
+	pub fn with_file(self, path: impl AsRef<Path>, content: impl Into<Vec<u8>>) -> Self {
+		self.insert(normalize(path.as_ref()), content.into());
+		self
+	}
+
+	fn insert(&self, path: PathBuf, content: Vec<u8>) {
+		let mut state = self.state.lock().unwrap();
+		state.writes += 1;
+		let modified = UNIX_EPOCH + Duration::from_nanos(state.writes);
+		state
+			.files
+			.insert(path.clone(), MemoryFile { content, modified });
+
+		// the watchers whose receiver has been dropped are removed
+		state.watchers.retain(|(directories, changes)| {
+			if directories
+				.iter()
+				.any(|directory| path.starts_with(directory))
+			{
+				changes.send(path.clone()).is_ok()
+			} else {
+				true
+			}
+		});
+	}
+}
+
+impl FileSystem for MemoryFileSystem {
+	fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+		let state = self.state.lock().unwrap();
+		match state.files.get(&normalize(path)) {
+			Some(file) => Ok(file.content.clone()),
+			None => Err(not_found(path)),
+		}
+	}
+
+	/// The parent directories of the file are created as needed
+	fn write(&self, path: &Path, content: &[u8]) -> io::Result<()> {
+		let path = normalize(path);
+		if is_directory(&self.state.lock().unwrap().files, &path) {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidInput,
+				format!("\"{}\" is a directory", path.display()),
+			));
+		}
+		self.insert(path, content.to_vec());
+		Ok(())
+	}
+
+	fn metadata(&self, path: &Path) -> io::Result<Metadata> {
+		let state = self.state.lock().unwrap();
+		let normalized = normalize(path);
+		match state.files.get(&normalized) {
+			Some(file) => Ok(Metadata {
+				kind: FileKind::File,
+				len: file.content.len() as u64,
+				modified: Some(file.modified),
+			}),
+			None if is_directory(&state.files, &normalized) => Ok(Metadata {
+				kind: FileKind::Directory,
+				len: 0,
+				modified: None,
+			}),
+			None => Err(not_found(path)),
+		}
+	}
+
+	fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+		self.metadata(path).map(|_| normalize(path))
+	}
+
+	fn walk(&self, directory: &Path, options: &WalkOptions) -> Walk {
+		let state = self.state.lock().unwrap();
+		let mut walk = Walk::default();
+		let walker = Walker {
+			files: &state.files,
+			options,
+		};
+		walker.walk_directory(&normalize(directory), directory, &mut Vec::new(), &mut walk);
+		walk
+	}
+
+	/// The directories are watched until the receiver of `changes` is dropped
+	fn watch(&self, directories: &[PathBuf], changes: Sender<PathBuf>) -> io::Result<WatchGuard> {
+		let mut state = self.state.lock().unwrap();
+		let directories: Vec<_> = directories.iter().map(|path| normalize(path)).collect();
+		if let Some(missing) = directories
+			.iter()
+			.find(|directory| !is_directory(&state.files, directory))
+		{
+			return Err(not_found(missing));
+		}
+		state.watchers.push((directories, changes));
+		Ok(Box::new(()))
+	}
+}
+
+/// Walks the directories of a [MemoryFileSystem]
+struct Walker<'a> {
+	files: &'a BTreeMap<PathBuf, MemoryFile>,
+	options: &'a WalkOptions<'a>,
+}
+
+impl Walker<'_> {
+	/// Walks `directory`, the collected paths start with `prefix` rather than the normalized
+	/// path of the directory. `ignores` holds the ignore files of the parent directories, from
+	/// the lowest to the highest precedence
+	fn walk_directory(
+		&self,
+		directory: &Path,
+		prefix: &Path,
+		ignores: &mut Vec<Gitignore>,
+		walk: &mut Walk,
+	) {
+		let parent_ignores = ignores.len();
+		if self.options.respect_ignore_files {
+			let file_names = IGNORE_FILE_NAMES
+				.iter()
+				.chain(self.options.custom_ignore_files);
+			for file_name in file_names {
+				if let Some(file) = self.files.get(&directory.join(file_name)) {
+					ignores.push(ignore_file(directory, &file.content));
+				}
+			}
+		}
+
+		for (name, kind) in self.entries(directory) {
+			if name == ".git" {
+				continue;
+			}
+			let path = directory.join(&name);
+			let prefixed = prefix.join(&name);
+			if is_ignored(ignores, &path, kind == FileKind::Directory) {
+				if self.options.collect_ignored {
+					match kind {
+						FileKind::File => walk.ignored_files.push(prefixed),
+						FileKind::Directory => walk.ignored_directories.push(prefixed),
+					}
+				}
+				continue;
+			}
+			match kind {
+				FileKind::File => walk.files.push(prefixed),
+				FileKind::Directory => self.walk_directory(&path, &prefixed, ignores, walk),
+			}
+		}
+
+		ignores.truncate(parent_ignores);
+	}
+
+	/// The files and the directories directly inside the directory, sorted by name
+	fn entries(&self, directory: &Path) -> BTreeMap<OsString, FileKind> {
+		let mut entries = BTreeMap::new();
+		for path in descendants(self.files, directory) {
+			let mut components = path.strip_prefix(directory).unwrap().components();
+			if let Some(name) = components.next() {
+				let kind = if components.next().is_some() {
+					FileKind::Directory
+				} else {
+					FileKind::File
+				};
+				entries.insert(name.as_os_str().to_os_string(), kind);
+			}
+		}
+		entries
+	}
+}
+
+/// The paths of the files inside the directory, at any depth. The paths of a [BTreeMap] are
+/// sorted by component, so they come right after the path of the directory
+fn descendants<'a>(
+	files: &'a BTreeMap<PathBuf, MemoryFile>,
+	directory: &'a Path,
+) -> impl Iterator<Item = &'a PathBuf> {
+	files
+		.range::<Path, _>((Bound::Excluded(directory), Bound::Unbounded))
+		.map(|(path, _)| path)
+		.take_while(move |path| path.starts_with(directory))
+}
+
+fn is_directory(files: &BTreeMap<PathBuf, MemoryFile>, path: &Path) -> bool {
+	descendants(files, path).next().is_some()
+}
+
+/// Parses an ignore file of the directory, the invalid patterns are skipped
+fn ignore_file(directory: &Path, content: &[u8]) -> Gitignore {
+	let mut builder = GitignoreBuilder::new(directory);
+	for line in String::from_utf8_lossy(content).lines() {
+		builder.add_line(None, line).ok();
+	}
+	builder.build().unwrap_or_else(|_| Gitignore::empty())
+}
+
+/// The closest ignore file that matches the path decides if it's ignored
+fn is_ignored(ignores: &[Gitignore], path: &Path, is_dir: bool) -> bool {
+	for ignore in ignores.iter().rev() {
+		match ignore.matched(path, is_dir) {
+			Match::None => continue,
+			matched => return matched.is_ignore(),
+		}
+	}
+	false
+}
+
+/// The absolute path, without `.` and `..` components
+fn normalize(path: &Path) -> PathBuf {
+	let mut normalized = PathBuf::from(Component::RootDir.as_os_str());
+	for component in path.components() {
+		match component {
+			Component::Prefix(prefix) => normalized = PathBuf::from(prefix.as_os_str()),
+			Component::RootDir => normalized.push(component.as_os_str()),
+			Component::CurDir => {}
+			Component::ParentDir => {
+				normalized.pop();
+			}
+			Component::Normal(name) => normalized.push(name),
+		}
+	}
+	normalized
+}
+
+fn not_found(path: &Path) -> io::Error {
+	io::Error::new(
+		io::ErrorKind::NotFound,
+		format!("\"{}\" doesn't exist", path.display()),
+	)
+}
+
+#[cfg(test)]
+mod test {
+	use super::MemoryFileSystem;
+	use crate::{FileSystem, WalkOptions};
+	use std::path::{Path, PathBuf};
+	use std::sync::mpsc::channel;
+
+	#[test]
+	fn walks_directories() {
+		let fs = MemoryFileSystem::default()
+			.with_file(".gitignore", "node_modules\n*.min.js\n")
+			.with_file("src/.romeignore", "generated\n")
+			.with_file("src/index.js", "")
+			.with_file("src/index.min.js", "")
+			.with_file("src/generated/a.js", "")
+			.with_file("src/.git/HEAD", "")
+			.with_file("node_modules/lib/index.js", "");
+
+		let options = WalkOptions {
+			respect_ignore_files: true,
+			custom_ignore_files: &[".romeignore"],
+			collect_ignored: true,
+		};
+		let walk = fs.walk(Path::new("."), &options);
+		assert_eq!(
+			walk.files,
+			vec![
+				Path::new("./.gitignore"),
+				Path::new("./src/.romeignore"),
+				Path::new("./src/index.js")
+			]
+		);
+		assert_eq!(walk.ignored_files, vec![Path::new("./src/index.min.js")]);
+		assert_eq!(
+			walk.ignored_directories,
+			vec![Path::new("./node_modules"), Path::new("./src/generated")]
+		);
+
+		let walk = fs.walk(Path::new("/src"), &WalkOptions::default());
+		assert_eq!(walk.files.len(), 4);
+		assert!(fs.walk(Path::new("missing"), &options).files.is_empty());
+	}
+
+	#[test]
+	fn reads_and_watches_files() {
+		let fs = MemoryFileSystem::default().with_file("src/index.js", "let a;");
+		assert_eq!(
+			fs.canonicalize(Path::new("src/../src/./index.js")).unwrap(),
+			Path::new("/src/index.js")
+		);
+		assert!(fs.read(Path::new("src")).is_err());
+		assert!(fs.write(Path::new("src"), b"").is_err());
+
+		let (changes, received) = channel();
+		let _guard = fs.watch(&[PathBuf::from("src")], changes).unwrap();
+		let modified = fs.metadata(Path::new("src/index.js")).unwrap().modified;
+		fs.write(Path::new("src/index.js"), b"let b;").unwrap();
+		fs.write(Path::new("README.md"), b"").unwrap();
+
+		assert_eq!(fs.read(Path::new("/src/index.js")).unwrap(), b"let b;");
+		assert_ne!(
+			fs.metadata(Path::new("src/index.js")).unwrap().modified,
+			modified
+		);
+		assert_eq!(
+			received.try_iter().collect::<Vec<_>>(),
+			vec![Path::new("/src/index.js")]
+		);
+		assert!(fs.watch(&[PathBuf::from("missing")], channel().0).is_err());
+	}
+}
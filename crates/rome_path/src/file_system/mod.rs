@@ -0,0 +1,99 @@
+//! The file system used by Rome to read, write, walk and watch the files.
+//!
+//! [OsFileSystem] is the file system of the operating system, while [MemoryFileSystem] holds
+//! the files in memory, so the files can be processed without touching the disk, like the
+//! unsaved buffers of an editor or the files of a test
+mod memory;
+mod os;
+
+pub use memory::MemoryFileSystem;
+pub use os::OsFileSystem;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
+use std::time::SystemTime;
+
+/// The names of the ignore files that are always respected when walking a directory, along
+/// with the ones of [WalkOptions]
+pub const IGNORE_FILE_NAMES: &[&str] = &[".gitignore", ".ignore"];
+
+/// The kind of an entry of the file system
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum FileKind {
+	File,
+	Directory,
+}
+
+/// The metadata of a file or a directory, the symbolic links are followed
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Metadata {
+	pub kind: FileKind,
+	/// The size of the file in bytes, zero for a directory
+	pub len: u64,
+	/// The last time the file has been modified, if the file system tracks it
+	pub modified: Option<SystemTime>,
+}
+
+impl Metadata {
+	pub fn is_file(&self) -> bool {
+		self.kind == FileKind::File
+	}
+
+	pub fn is_dir(&self) -> bool {
+		self.kind == FileKind::Directory
+	}
+}
+
+/// Options that control how a directory is walked
+#[derive(Debug, Default)]
+pub struct WalkOptions<'a> {
+	/// Whether the ignore files are respected, following the semantics of `.gitignore`
+	pub respect_ignore_files: bool,
+	/// The names of the ignore files respected along with [IGNORE_FILE_NAMES], they
+	/// take precedence over them
+	pub custom_ignore_files: &'a [&'a str],
+	/// Whether the ignored paths are collected
+	pub collect_ignored: bool,
+}
+
+/// The outcome of [FileSystem::walk]. The paths start with the walked directory
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct Walk {
+	/// The files that haven't been ignored
+	pub files: Vec<PathBuf>,
+	/// The ignored files, only collected when requested by [WalkOptions]
+	pub ignored_files: Vec<PathBuf>,
+	/// The ignored directories, whose content isn't walked. They are only collected when
+	/// requested by [WalkOptions]
+	pub ignored_directories: Vec<PathBuf>,
+}
+
+/// Keeps watching the directories passed to [FileSystem::watch], until it's dropped
+pub type WatchGuard = Box<dyn Send>;
+
+/// The operations on the files needed by Rome.
+///
+/// The file systems are shared between the threads that process the files, hence they must
+/// be [Send] and [Sync]
+pub trait FileSystem: Send + Sync {
+	/// Reads the whole content of the file
+	fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+
+	/// Replaces the content of the file, the file is created when it doesn't exist
+	fn write(&self, path: &Path, content: &[u8]) -> io::Result<()>;
+
+	/// Reads the metadata of the file or the directory
+	fn metadata(&self, path: &Path) -> io::Result<Metadata>;
+
+	/// Returns the absolute path of an existing file or directory, without `.` and `..`
+	/// components and with the symbolic links resolved
+	fn canonicalize(&self, path: &Path) -> io::Result<PathBuf>;
+
+	/// Walks the directory recursively, collecting its files. The `.git` directory is
+	/// never walked, and a directory that doesn't exist doesn't have any file
+	fn walk(&self, directory: &Path, options: &WalkOptions) -> Walk;
+
+	/// Watches the directories and their subdirectories, sending the canonical path of the
+	/// files created or modified to `changes`. A change can be sent more than once
+	fn watch(&self, directories: &[PathBuf], changes: Sender<PathBuf>) -> io::Result<WatchGuard>;
+}
@@ -0,0 +1,212 @@
+use super::{FileKind, FileSystem, Metadata, Walk, WalkOptions, WatchGuard};
+use ignore::WalkBuilder;
+use notify::{watcher, DebouncedEvent, RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions, Permissions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{channel, Sender};
+use std::thread;
+use std::time::Duration;
+use walkdir::WalkDir;
+
+/// How long a file has to stay untouched before its change is reported
+const DEBOUNCE_DELAY: Duration = Duration::from_millis(100);
+
+/// The file system of the operating system
+#[derive(Debug, Default, Clone, Copy)]
+pub struct OsFileSystem;
+
+impl FileSystem for OsFileSystem {
+	fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+		fs::read(path)
+	}
+
+	/// The content is written in a temporary file, in the same directory, that is then renamed
+	/// over the file: if the process is killed, the file has either the old or the new content.
+	/// The permissions of the file are preserved, and when the file is a symbolic link the
+	/// content of its target is replaced, while the link is kept.
+	fn write(&self, path: &Path, content: &[u8]) -> io::Result<()> {
+		let target = match fs::symlink_metadata(path) {
+			Ok(metadata) if metadata.file_type().is_symlink() => fs::canonicalize(path)?,
+			_ => path.to_path_buf(),
+		};
+		let permissions = fs::metadata(&target)
+			.ok()
+			.map(|metadata| metadata.permissions());
+
+		let temporary = temporary_path(&target);
+		let result = write_file(&temporary, content, permissions)
+			.and_then(|_| fs::rename(&temporary, &target));
+		if result.is_err() {
+			// the temporary file may not exist, depending on where the writing failed
+			fs::remove_file(&temporary).ok();
+		}
+		result?;
+
+		sync_parent_directory(&target);
+		Ok(())
+	}
+
+	fn metadata(&self, path: &Path) -> io::Result<Metadata> {
+		let metadata = fs::metadata(path)?;
+		Ok(Metadata {
+			kind: if metadata.is_dir() {
+				FileKind::Directory
+			} else {
+				FileKind::File
+			},
+			len: metadata.len(),
+			modified: metadata.modified().ok(),
+		})
+	}
+
+	fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+		fs::canonicalize(path)
+	}
+
+	/// The ignore files of the parent directories, the global ignore file of Git and the
+	/// `.git/info/exclude` file are respected as well
+	fn walk(&self, directory: &Path, options: &WalkOptions) -> Walk {
+		let mut builder = WalkBuilder::new(directory);
+		builder
+			.standard_filters(options.respect_ignore_files)
+			.hidden(false)
+			.require_git(false)
+			.filter_entry(|entry| entry.file_name() != ".git");
+		if options.respect_ignore_files {
+			for file_name in options.custom_ignore_files {
+				builder.add_custom_ignore_filename(file_name);
+			}
+		}
+
+		let mut walk = Walk::default();
+		// all the paths that haven't been ignored, only needed to find the ignored paths
+		let mut walked = HashSet::new();
+		for entry in builder.build().filter_map(Result::ok) {
+			if matches!(entry.file_type(), Some(file_type) if file_type.is_file()) {
+				walk.files.push(entry.path().to_path_buf());
+			}
+			if options.collect_ignored {
+				walked.insert(entry.into_path());
+			}
+		}
+
+		if options.collect_ignored && options.respect_ignore_files {
+			// walks the directory again, without ignoring anything, to find the paths that
+			// haven't been walked. The content of an ignored directory isn't walked
+			WalkDir::new(directory)
+				.into_iter()
+				.filter_entry(|entry| {
+					if entry.depth() == 0 || walked.contains(entry.path()) {
+						return true;
+					}
+					if entry.file_name() != ".git" {
+						let path = entry.path().to_path_buf();
+						if entry.file_type().is_dir() {
+							walk.ignored_directories.push(path);
+						} else {
+							walk.ignored_files.push(path);
+						}
+					}
+					false
+				})
+				.for_each(drop);
+		}
+
+		walk
+	}
+
+	/// The events are debounced: the saves of a file in rapid succession are reported once
+	fn watch(&self, directories: &[PathBuf], changes: Sender<PathBuf>) -> io::Result<WatchGuard> {
+		let (sender, events) = channel();
+		let mut watcher = watcher(sender, DEBOUNCE_DELAY).map_err(to_io_error)?;
+		for directory in directories {
+			watcher
+				.watch(directory, RecursiveMode::Recursive)
+				.map_err(to_io_error)?;
+		}
+
+		// the events stop once the watcher is dropped
+		thread::spawn(move || {
+			for event in events {
+				if let Some(path) = changed_path(event) {
+					if changes.send(path).is_err() {
+						break;
+					}
+				}
+			}
+		});
+		Ok(Box::new(watcher))
+	}
+}
+
+/// The canonical path of the file created or modified by the event
+fn changed_path(event: DebouncedEvent) -> Option<PathBuf> {
+	let path = match event {
+		DebouncedEvent::Create(path) | DebouncedEvent::Write(path) => path,
+		DebouncedEvent::Rename(_, to) => to,
+		_ => return None,
+	};
+
+	if path.is_file() {
+		Some(path.canonicalize().unwrap_or(path))
+	} else {
+		None
+	}
+}
+
+fn to_io_error(error: notify::Error) -> io::Error {
+	let kind = match error {
+		notify::Error::Io(error) => return error,
+		notify::Error::PathNotFound | notify::Error::WatchNotFound => io::ErrorKind::NotFound,
+		// the path can't be watched, like a path that isn't a file or a directory
+		notify::Error::Generic(_) => io::ErrorKind::InvalidInput,
+	};
+	io::Error::new(kind, error.to_string())
+}
+
+/// The path of a file that doesn't exist yet, next to `path`
+fn temporary_path(path: &Path) -> PathBuf {
+	static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+	let file_name = path
+		.file_name()
+		.map(|name| name.to_string_lossy().into_owned())
+		.unwrap_or_default();
+	path.with_file_name(format!(
+		".{}.{}-{}.rome-tmp",
+		file_name,
+		process::id(),
+		COUNTER.fetch_add(1, Ordering::Relaxed)
+	))
+}
+
+/// Writes a new file and flushes its content to the disk
+fn write_file(path: &Path, content: &[u8], permissions: Option<Permissions>) -> io::Result<()> {
+	let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+	file.write_all(content)?;
+	if let Some(permissions) = permissions {
+		file.set_permissions(permissions)?;
+	}
+	file.sync_all()
+}
+
+/// Flushes the rename to the disk. It's only possible on Unix, and a failure isn't an error,
+/// as the content of the file has already been written
+fn sync_parent_directory(path: &Path) {
+	if cfg!(unix) {
+		if let Some(directory) = path.parent() {
+			let directory = if directory.as_os_str().is_empty() {
+				Path::new(".")
+			} else {
+				directory
+			};
+			if let Ok(directory) = File::open(directory) {
+				directory.sync_all().ok();
+			}
+		}
+	}
+}
@@ -0,0 +1,153 @@
+//! Formats the files with the formatter of their handler
+use crate::{strip_byte_order_mark, FileError, RomePath, BYTE_ORDER_MARK};
+use rome_formatter::{ByteOrderMark, FormatError, FormatOptions, FormatResult, Formatted};
+use std::{error::Error, fmt};
+
+/// An error that prevented a file from being formatted
+#[derive(Debug, PartialEq)]
+pub enum FormatFileError {
+	/// The file to format can't be read, or the formatted code can't be written
+	File(FileError),
+	/// The content of the file can't be formatted
+	Format(FormatError),
+}
+
+impl fmt::Display for FormatFileError {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			FormatFileError::File(error) => error.fmt(fmt),
+			FormatFileError::Format(error) => error.fmt(fmt),
+		}
+	}
+}
+
+impl Error for FormatFileError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			FormatFileError::File(error) => Some(error),
+			FormatFileError::Format(error) => Some(error),
+		}
+	}
+}
+
+impl From<FileError> for FormatFileError {
+	fn from(error: FileError) -> Self {
+		FormatFileError::File(error)
+	}
+}
+
+impl From<FormatError> for FormatFileError {
+	fn from(error: FormatError) -> Self {
+		FormatFileError::Format(error)
+	}
+}
+
+/// The outcome of [format_file_and_save]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum FileStatus {
+	/// The formatted code was different from the content of the file, and it has been saved
+	Formatted,
+	/// The content of the file was already formatted, nothing has been written
+	Unchanged,
+}
+
+/// Formats a source that is held in memory, like the content of an unsaved buffer.
+///
+/// The path is only used to find the handler of the source, the file doesn't need to exist.
+/// The byte order mark at the start of the source is handled according to [ByteOrderMark].
+///
+/// ```rust
+/// use rome_core::create_app;
+/// use rome_formatter::{ByteOrderMark, FormatOptions};
+/// use rome_path::{format_source, RomePath};
+///
+/// let app = create_app();
+/// let rome_path = RomePath::new("buffer.js").deduce_handler(&app);
+/// let result = format_source(&rome_path, "let a  =  1", FormatOptions::default()).unwrap();
+/// assert_eq!(result.code(), "let a = 1;\n");
+///
+/// let result = format_source(&rome_path, "\u{feff}let a  =  1", FormatOptions::default()).unwrap();
+/// assert_eq!(result.code(), "\u{feff}let a = 1;\n");
+///
+/// let options = FormatOptions {
+///     byte_order_mark: ByteOrderMark::Remove,
+///     ..FormatOptions::default()
+/// };
+/// let result = format_source(&rome_path, "\u{feff}let a  =  1", options).unwrap();
+/// assert_eq!(result.code(), "let a = 1;\n");
+/// ```
+pub fn format_source(
+	rome_path: &RomePath,
+	content: &str,
+	options: FormatOptions,
+) -> FormatResult<Formatted> {
+	let (content, has_byte_order_mark) = strip_byte_order_mark(content);
+	let keep_byte_order_mark =
+		has_byte_order_mark && options.byte_order_mark == ByteOrderMark::Keep;
+
+	let handler = rome_path
+		.get_handler()
+		.ok_or(FormatError::UnsupportedLanguage)?;
+	if !handler.capabilities().format {
+		return Err(FormatError::CapabilityDisabled);
+	}
+
+	handler.format(content, options).map(|formatted| {
+		if keep_byte_order_mark {
+			Formatted::new(&format!("{}{}", BYTE_ORDER_MARK, formatted.code()))
+		} else {
+			formatted
+		}
+	})
+}
+
+/// Formats the content of the file
+pub fn format(
+	rome_path: &mut RomePath,
+	options: FormatOptions,
+) -> Result<Formatted, FormatFileError> {
+	let buffer = rome_path.read_to_string()?;
+	Ok(format_source(rome_path, buffer.as_str(), options)?)
+}
+
+/// Formats the file and writes the result back, only if the formatted code differs from
+/// the current content of the file
+pub fn format_file_and_save(
+	rome_path: &mut RomePath,
+	options: FormatOptions,
+) -> Result<FileStatus, FormatFileError> {
+	let buffer = rome_path.read_to_string()?;
+	let result = format_source(rome_path, buffer.as_str(), options)?;
+	if result.code() == &buffer {
+		return Ok(FileStatus::Unchanged);
+	}
+	rome_path.save(result.code())?;
+	Ok(FileStatus::Formatted)
+}
+
+/// The outcome of [check_file]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CheckResult {
+	/// The current content of the file
+	pub source: String,
+	/// The result of formatting the content of the file
+	pub formatted: Formatted,
+}
+
+impl CheckResult {
+	/// Returns `true` if the content of the file is already formatted
+	pub fn is_formatted(&self) -> bool {
+		self.formatted.code() == &self.source
+	}
+}
+
+/// Formats the file without writing anything on disk, returning both the current
+/// content of the file and its formatted version
+pub fn check_file(
+	rome_path: &mut RomePath,
+	options: FormatOptions,
+) -> Result<CheckResult, FormatFileError> {
+	let source = rome_path.read_to_string()?;
+	let formatted = format_source(rome_path, source.as_str(), options)?;
+	Ok(CheckResult { source, formatted })
+}
@@ -2,16 +2,140 @@
 //! It is a small wrapper around [path::PathBuf] but it is also able to
 //! give additional information around the the file that holds:
 //! - the [FileHandlers] for the specific file
-//! - shortcuts to open/write to the file
+//! - shortcuts to read/write the file, through a [FileSystem]
+//! - shortcuts to format the file with its handler
+mod file_system;
+mod format;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
+pub use file_system::{
+	FileKind, FileSystem, MemoryFileSystem, Metadata, OsFileSystem, Walk, WalkOptions, WatchGuard,
+	IGNORE_FILE_NAMES,
+};
+pub use format::{
+	check_file, format, format_file_and_save, format_source, CheckResult, FileStatus,
+	FormatFileError,
+};
 use rome_core::{file_handlers::ExtensionHandler, App};
-use std::{fs::File, io::Write, ops::Deref, path::PathBuf};
+use rslint_errors::{file::FileId, Diagnostic};
+use std::{
+	error::Error,
+	fmt, io,
+	ops::Deref,
+	path::{Path, PathBuf},
+};
+
+/// The byte order mark, that some editors write at the start of the UTF-8 files
+pub const BYTE_ORDER_MARK: char = '\u{feff}';
+
+/// The files without extension that are bigger than this aren't read to find their language
+const MAX_SNIFFED_SIZE: u64 = 1024 * 1024;
 
-pub struct RomePath<'handler> {
+/// Removes the byte order mark at the start of `content`, returning the content without it
+/// along with whether it was present
+pub fn strip_byte_order_mark(content: &str) -> (&str, bool) {
+	match content.strip_prefix(BYTE_ORDER_MARK) {
+		Some(content) => (content, true),
+		None => (content, false),
+	}
+}
+
+/// An error that happened while reading or writing a file
+#[derive(Debug)]
+pub enum FileError {
+	/// The file can't be opened or read
+	Read(io::Error),
+	/// The content of the file isn't valid UTF-8
+	InvalidUtf8 {
+		/// The byte offset of the first invalid byte
+		offset: usize,
+	},
+	/// The file starts with the byte order mark of UTF-16, which isn't supported
+	Utf16,
+	/// The file can't be created or written
+	Write(io::Error),
+}
+
+impl FileError {
+	/// Creates the diagnostic of the error, for the file at `path`
+	pub fn to_diagnostic(&self, file_id: FileId, path: &Path) -> Diagnostic {
+		match self {
+			FileError::Read(error) => Diagnostic::error(
+				file_id,
+				"io",
+				format!("Cannot read \"{}\": {}", path.display(), error),
+			),
+			FileError::InvalidUtf8 { offset } => Diagnostic::error(
+				file_id,
+				"io",
+				format!("The file \"{}\" isn't valid UTF-8", path.display()),
+			)
+			.footer_note(format!("the first invalid byte is at offset {}", offset)),
+			FileError::Utf16 => Diagnostic::error(
+				file_id,
+				"io",
+				format!("The file \"{}\" is encoded in UTF-16", path.display()),
+			)
+			.footer_help("only UTF-8 is supported, convert the file to UTF-8"),
+			FileError::Write(error) => Diagnostic::error(
+				file_id,
+				"io",
+				format!("Cannot write \"{}\": {}", path.display(), error),
+			),
+		}
+	}
+}
+
+/// The I/O errors are compared by kind, as [io::Error] can't be compared
+impl PartialEq for FileError {
+	fn eq(&self, other: &Self) -> bool {
+		match (self, other) {
+			(FileError::Read(error), FileError::Read(other))
+			| (FileError::Write(error), FileError::Write(other)) => error.kind() == other.kind(),
+			(FileError::InvalidUtf8 { offset }, FileError::InvalidUtf8 { offset: other }) => {
+				offset == other
+			}
+			(FileError::Utf16, FileError::Utf16) => true,
+			_ => false,
+		}
+	}
+}
+
+impl fmt::Display for FileError {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			FileError::Read(error) => write!(fmt, "cannot read the file: {}", error),
+			FileError::InvalidUtf8 { offset } => write!(
+				fmt,
+				"the file isn't valid UTF-8, the first invalid byte is at offset {}",
+				offset
+			),
+			FileError::Utf16 => write!(
+				fmt,
+				"the file is encoded in UTF-16, only UTF-8 is supported"
+			),
+			FileError::Write(error) => write!(fmt, "cannot write the file: {}", error),
+		}
+	}
+}
+
+impl Error for FileError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			FileError::Read(error) | FileError::Write(error) => Some(error),
+			FileError::InvalidUtf8 { .. } | FileError::Utf16 => None,
+		}
+	}
+}
+
+pub struct RomePath<'a> {
 	file: PathBuf,
-	handler: Option<&'handler dyn ExtensionHandler>,
+	handler: Option<&'a dyn ExtensionHandler>,
+	file_system: &'a dyn FileSystem,
 }
 
-impl<'handler> Deref for RomePath<'handler> {
+impl<'a> Deref for RomePath<'a> {
 	type Target = PathBuf;
 
 	fn deref(&self) -> &Self::Target {
@@ -19,17 +143,32 @@ impl<'handler> Deref for RomePath<'handler> {
 	}
 }
 
-impl<'handler> RomePath<'handler> {
-	pub fn new(path_to_file: &str) -> Self {
+impl<'a> RomePath<'a> {
+	/// Creates the path of a file of the [OsFileSystem]
+	pub fn new(path_to_file: impl Into<PathBuf>) -> Self {
 		Self {
-			file: PathBuf::from(path_to_file),
+			file: path_to_file.into(),
 			handler: None,
+			file_system: &OsFileSystem,
 		}
 	}
 
-	/// Deduce the file handler based on the extension of the file.
+	/// Reads and writes the file through `file_system`, rather than the [OsFileSystem]
+	pub fn with_file_system(mut self, file_system: &'a dyn FileSystem) -> Self {
+		self.file_system = file_system;
+		self
+	}
+
+	/// The file system where the file is read and written
+	pub fn file_system(&self) -> &'a dyn FileSystem {
+		self.file_system
+	}
+
+	/// Deduce the file handler based on the name or the extension of the file. The files
+	/// without extension are read, their language is guessed from their content, like their
+	/// shebang, see [App::handler_for_content].
 	///
-	/// Any error will default to the base file handler for now.
+	/// The files that no handler claims get the unknown handler.
 	///
 	///
 	/// ```rust
@@ -42,7 +181,7 @@ impl<'handler> RomePath<'handler> {
 	/// let app = create_app();
 	/// let file = RomePath::new("file.js").deduce_handler(&app);
 	/// let handler = file.get_handler();
-	/// let expected = JsFileHandler {};
+	/// let expected = JsFileHandler::script();
 	/// assert_eq!(
 	///   handler.unwrap().capabilities().format,
 	///   expected.capabilities().format
@@ -52,33 +191,61 @@ impl<'handler> RomePath<'handler> {
 	///  expected.capabilities().lint
 	/// )
 	/// ```
-	pub fn deduce_handler(mut self, app: &'handler App) -> Self {
-		if self.extension().is_none() {
-			return self;
-		}
-		let extension = self.extension().unwrap().to_str().unwrap();
-
-		if let Some(handler) = app.get_handler(extension) {
-			self.handler = Some(handler);
+	pub fn deduce_handler(self, app: &'a App) -> Self {
+		let content = if self.file.extension().is_none() {
+			self.read_to_sniff()
 		} else {
-			// we know that unknown is hardcoded
-			self.handler = Some(app.get_handler("unknown").unwrap());
-		}
+			None
+		};
+		self.deduce_handler_with_content(app, content.as_deref().unwrap_or_default())
+	}
+
+	/// Deduce the file handler like [RomePath::deduce_handler], but the language of the files
+	/// without extension is guessed from `content` rather than from the file, which doesn't
+	/// need to exist. It's meant for the sources held in memory, like an unsaved buffer
+	pub fn deduce_handler_with_content(mut self, app: &'a App, content: &str) -> Self {
+		let handler = app
+			.handler_for_file(&self.file, content)
+			.unwrap_or_else(|| app.unknown_handler());
+		self.handler = Some(handler);
 
 		self
 	}
 
-	// TODO: handle error with diagnostic?
-	/// Opens a file and returns a [File] in write mode
-	pub fn open(&self) -> File {
-		File::open(&self.file).expect("cannot open the file to format")
+	/// Reads the file to guess its language, unless it's too big to be a source file
+	fn read_to_sniff(&self) -> Option<String> {
+		let metadata = self.file_system.metadata(&self.file).ok()?;
+		if !metadata.is_file() || metadata.len > MAX_SNIFFED_SIZE {
+			return None;
+		}
+		self.read_to_string().ok()
 	}
 
-	/// Accepts a file opened in read mode and saves into it
-	pub fn save(&mut self, content: &str) -> Result<(), std::io::Error> {
-		let mut file_to_write = File::create(&self.file).unwrap();
-		// TODO: handle error with diagnostic
-		file_to_write.write_all(content.as_bytes())
+	/// Reads the content of the file, which must be valid UTF-8.
+	///
+	/// The byte order mark is part of the content, it can be removed with [strip_byte_order_mark]
+	pub fn read_to_string(&self) -> Result<String, FileError> {
+		let content = self.file_system.read(&self.file).map_err(FileError::Read)?;
+		// the byte order marks of UTF-16, little and big endian
+		if content.starts_with(&[0xff, 0xfe]) || content.starts_with(&[0xfe, 0xff]) {
+			return Err(FileError::Utf16);
+		}
+		String::from_utf8(content).map_err(|error| FileError::InvalidUtf8 {
+			offset: error.utf8_error().valid_up_to(),
+		})
+	}
+
+	/// Replaces the content of the file with `content`, see [FileSystem::write].
+	///
+	/// Nothing is written when the file already has this content.
+	pub fn save(&mut self, content: &str) -> Result<(), FileError> {
+		let current = self.file_system.read(&self.file).ok();
+		if current.as_deref() == Some(content.as_bytes()) {
+			return Ok(());
+		}
+		self.file_system
+			.write(&self.file, content.as_bytes())
+			.map_err(FileError::Write)
 	}
 
 	/// Returns the current handler associated to the file.
@@ -92,10 +259,12 @@ impl<'handler> RomePath<'handler> {
 
 #[cfg(test)]
 mod test {
-	use crate::RomePath;
+	use crate::{FileError, MemoryFileSystem, RomePath};
 	use rome_core::{
 		create_app,
-		file_handlers::{javascript::JsFileHandler, ExtensionHandler},
+		file_handlers::{
+			javascript::JsFileHandler, typescript::TsFileHandler, ExtensionHandler, Language,
+		},
 	};
 
 	#[test]
@@ -103,7 +272,7 @@ mod test {
 		let app = create_app();
 		let file = RomePath::new("file.js").deduce_handler(&app);
 		let handler = file.get_handler();
-		let expected = JsFileHandler {};
+		let expected = JsFileHandler::script();
 		assert_eq!(
 			handler.unwrap().capabilities().format,
 			expected.capabilities().format
@@ -111,6 +280,93 @@ mod test {
 		assert_eq!(
 			handler.unwrap().capabilities().lint,
 			expected.capabilities().lint
-		)
+		);
+
+		// TypeScript can be linted, but not formatted yet
+		let file = RomePath::new("file.ts").deduce_handler(&app);
+		let handler = file.get_handler().unwrap();
+		assert_eq!(handler.language(), Language::Ts);
+		assert!(handler.capabilities().lint);
+		assert!(!handler.capabilities().format);
+		assert!(TsFileHandler::jsx().capabilities().lint);
+	}
+
+	#[test]
+	fn deduce_handler_from_content() {
+		let app = create_app();
+		let language_of = |rome_path: RomePath| rome_path.get_handler().unwrap().language();
+
+		let fs = MemoryFileSystem::default().with_file("bin/deploy", "#!/usr/bin/env node\n");
+		let file = RomePath::new("bin/deploy").with_file_system(&fs);
+		assert_eq!(language_of(file.deduce_handler(&app)), Language::Js);
+		let file = RomePath::new("bin/missing").with_file_system(&fs);
+		assert_eq!(language_of(file.deduce_handler(&app)), Language::Unknown);
+
+		let buffer = "{ \"name\": \"rome\" }";
+		assert_eq!(
+			language_of(RomePath::new(".eslintrc").deduce_handler_with_content(&app, buffer)),
+			Language::Json
+		);
+		// the content doesn't override the extension
+		assert_eq!(
+			language_of(RomePath::new("notes.txt").deduce_handler_with_content(&app, buffer)),
+			Language::Unknown
+		);
+	}
+
+	#[test]
+	fn reports_invalid_utf8() {
+		let fs = MemoryFileSystem::default()
+			.with_file("invalid.js", &b"let a = \"\xff\";"[..])
+			.with_file("utf16.js", &b"\xff\xfel\0e\0t\0"[..]);
+
+		match RomePath::new("invalid.js")
+			.with_file_system(&fs)
+			.read_to_string()
+		{
+			Err(FileError::InvalidUtf8 { offset }) => assert_eq!(offset, 9),
+			result => panic!("unexpected result {:?}", result),
+		}
+		assert!(matches!(
+			RomePath::new("missing.js")
+				.with_file_system(&fs)
+				.read_to_string(),
+			Err(FileError::Read(_))
+		));
+		assert_eq!(
+			RomePath::new("utf16.js")
+				.with_file_system(&fs)
+				.read_to_string(),
+			Err(FileError::Utf16)
+		);
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn saves_atomically() {
+		use crate::testing::TestDirectory;
+		use std::os::unix::fs::{symlink, MetadataExt, PermissionsExt};
+
+		let directory = TestDirectory::new("saves_atomically");
+		let file = directory.join("script.js");
+		std::fs::write(&file, "let a  =  1").unwrap();
+		std::fs::set_permissions(&file, std::fs::Permissions::from_mode(0o755)).unwrap();
+		let link = directory.join("link.js");
+		symlink(&file, &link).unwrap();
+
+		RomePath::new(&link).save("let a = 1;\n").unwrap();
+		assert_eq!(std::fs::read_to_string(&file).unwrap(), "let a = 1;\n");
+		assert!(std::fs::symlink_metadata(&link)
+			.unwrap()
+			.file_type()
+			.is_symlink());
+		let metadata = std::fs::metadata(&file).unwrap();
+		assert_eq!(metadata.permissions().mode() & 0o777, 0o755);
+		// only the file and the link are left
+		assert_eq!(std::fs::read_dir(&directory).unwrap().count(), 2);
+
+		// the same content isn't written again
+		RomePath::new(&file).save("let a = 1;\n").unwrap();
+		assert_eq!(std::fs::metadata(&file).unwrap().ino(), metadata.ino());
 	}
 }
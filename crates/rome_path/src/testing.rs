@@ -0,0 +1,78 @@
+//! Helpers for the tests that need real files, like the tests of [OsFileSystem](crate::OsFileSystem).
+//!
+//! They are available to the tests of the other crates through the `testing` feature.
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::{env, fs, process};
+
+/// Makes the names of the directories created by a process unique
+static DIRECTORY_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// An empty directory inside the temporary directory of the system, which is removed along
+/// with its content when dropped.
+///
+/// Its name is unique to the process, so the tests can run concurrently, even when they are
+/// run by several processes
+#[derive(Debug)]
+pub struct TestDirectory {
+	path: PathBuf,
+}
+
+impl TestDirectory {
+	/// Creates the directory, `name` is part of its name to find the files of a test
+	pub fn new(name: &str) -> Self {
+		let count = DIRECTORY_COUNT.fetch_add(1, Ordering::Relaxed);
+		let path = env::temp_dir().join(format!("rome_{}_{}_{}", name, process::id(), count));
+		// a process that has been killed may have left a directory with the same name
+		if path.exists() {
+			fs::remove_dir_all(&path).expect("cannot clean the test directory");
+		}
+		fs::create_dir_all(&path).expect("cannot create the test directory");
+		Self { path }
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+}
+
+impl Deref for TestDirectory {
+	type Target = Path;
+
+	fn deref(&self) -> &Self::Target {
+		&self.path
+	}
+}
+
+impl AsRef<Path> for TestDirectory {
+	fn as_ref(&self) -> &Path {
+		&self.path
+	}
+}
+
+impl Drop for TestDirectory {
+	fn drop(&mut self) {
+		// failing to clean the directory isn't a reason to fail the test
+		fs::remove_dir_all(&self.path).ok();
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::TestDirectory;
+	use std::fs;
+
+	#[test]
+	fn removes_directory() {
+		let directory = TestDirectory::new("removes_directory");
+		let other = TestDirectory::new("removes_directory");
+		assert_ne!(directory.path(), other.path());
+
+		fs::write(directory.join("index.js"), "let a = 1;\n").unwrap();
+		let path = directory.to_path_buf();
+		drop(directory);
+		assert!(!path.exists());
+		assert!(other.is_dir());
+	}
+}
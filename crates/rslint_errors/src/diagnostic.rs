@@ -8,6 +8,7 @@ use rslint_text_edit::*;
 /// A diagnostic message that can give information
 /// like errors or warnings.
 #[derive(Debug, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serialization", derive(serde::Serialize))]
 pub struct Diagnostic {
 	pub file_id: FileId,
 
@@ -400,6 +401,7 @@ impl Diagnostic {
 /// Everything that can be added to a diagnostic, like
 /// a suggestion that will be displayed under the actual error.
 #[derive(Debug, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serialization", derive(serde::Serialize))]
 pub struct SubDiagnostic {
 	pub severity: Severity,
 	pub msg: String,
@@ -408,6 +410,7 @@ pub struct SubDiagnostic {
 
 /// A note or help that is displayed under the diagnostic.
 #[derive(Debug, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serialization", derive(serde::Serialize))]
 pub struct Footer {
 	pub msg: String,
 	pub severity: Severity,
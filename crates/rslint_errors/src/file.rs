@@ -118,7 +118,7 @@ impl Span for TextRange {
 pub type FileId = usize;
 
 /// A range that is indexed in a specific file.
-#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serialization", derive(serde::Serialize))]
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct FileSpan {
 	pub file: FileId,
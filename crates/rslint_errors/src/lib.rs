@@ -20,7 +20,7 @@ pub use codespan::diagnostic::Severity;
 pub use termcolor;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serialization", derive(serde::Serialize))]
 pub enum DiagnosticTag {
 	Unnecessary,
 	Deprecated,
@@ -39,7 +39,7 @@ impl DiagnosticTag {
 
 /// Indicicates how a tool should manage this suggestion.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serialization", derive(serde::Serialize))]
 pub enum Applicability {
 	/// The suggestion is definitely what the user intended.
 	/// This suggestion should be automatically applied.
@@ -67,10 +67,12 @@ pub fn convert_to_lsp_diagnostic(
 		range: primary_label?,
 		severity: Some(severity_to_lsp_severity(diagnostic.severity)),
 		code: diagnostic.code.map(NumberOrString::String),
+		code_description: None,
 		source,
 		message,
 		related_information: Some(related_information),
 		tags,
+		data: None,
 	})
 }
 
@@ -216,8 +218,8 @@ fn location_to_position(
 		Err(LocationError::InvalidCharBoundary { given }.into())
 	} else {
 		let line_utf16 = line_str[..column].encode_utf16();
-		let character = line_utf16.count() as u64;
-		let line = line as u64;
+		let character = line_utf16.count() as u32;
+		let line = line as u32;
 
 		Ok(LspPosition { line, character })
 	}
@@ -267,7 +269,7 @@ where
 	})
 }
 
-pub fn character_to_line_offset(line: &str, character: u64) -> Result<usize, Error> {
+pub fn character_to_line_offset(line: &str, character: u32) -> Result<usize, Error> {
 	let line_len = line.len();
 	let mut character_offset = 0;
 
@@ -280,7 +282,7 @@ pub fn character_to_line_offset(line: &str, character: u64) -> Result<usize, Err
 			return Ok(line_len - chars_off - ch_off);
 		}
 
-		character_offset += ch.len_utf16() as u64;
+		character_offset += ch.len_utf16() as u32;
 	}
 
 	// Handle positions after the last character on the line
@@ -304,8 +306,18 @@ where
 {
 	let source = files.source(file_id).ok_or(Error::MissingFile)?;
 
-	let line_span = files.line_range(file_id, position.line as usize).unwrap();
-	let line_str = source.get(line_span.clone()).unwrap();
+	let line_span = files
+		.line_range(file_id, position.line as usize)
+		.ok_or_else(|| LineIndexOutOfBoundsError {
+			given: position.line as usize,
+			max: source.lines().count(),
+		})?;
+	let line_str = source
+		.get(line_span.clone())
+		.ok_or_else(|| SpanOutOfBoundsError {
+			given: line_span.clone(),
+			span: 0..source.len(),
+		})?;
 
 	let byte_offset = character_to_line_offset(line_str, position.character)?;
 
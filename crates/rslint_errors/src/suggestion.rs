@@ -6,6 +6,7 @@ use std::ops::Range;
 /// can be reported to the user, and can be automatically
 /// applied if it has the right [`Applicability`].
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialization", derive(serde::Serialize))]
 pub struct CodeSuggestion {
 	/// If the `FileId` is `None`, it's in the same file as
 	/// his parent.
@@ -18,12 +19,14 @@ pub struct CodeSuggestion {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialization", derive(serde::Serialize))]
 pub enum SuggestionChange {
 	Indels(Vec<Indel>),
 	String(String),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialization", derive(serde::Serialize))]
 pub enum SuggestionStyle {
 	/// Do not show the suggestion at all
 	DontShow,
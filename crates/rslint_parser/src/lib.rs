@@ -73,6 +73,7 @@ mod tests;
 
 #[macro_use]
 pub mod ast;
+pub mod semantic_tokens;
 pub mod syntax;
 pub mod util;
 
@@ -173,6 +174,9 @@ pub struct Syntax {
 	pub global_return: bool,
 	pub class_fields: bool,
 	pub decorators: bool,
+	/// A TypeScript file is an ECMAScript module, like the `.mts` files. The other files are
+	/// modules when their kind is [FileKind::Module]
+	pub typescript_module: bool,
 }
 
 impl Syntax {
@@ -221,6 +225,21 @@ impl Syntax {
 		self.file_kind = FileKind::TypeScript;
 		self.class_fields().decorators().top_level_await()
 	}
+
+	/// A TypeScript file that is an ECMAScript module, which is always in strict mode
+	pub fn typescript_module(mut self) -> Self {
+		self.typescript_module = true;
+		self.typescript()
+	}
+
+	/// Whether the file is an ECMAScript module rather than a script
+	pub fn is_module(&self) -> bool {
+		match self.file_kind {
+			FileKind::Script => false,
+			FileKind::Module => true,
+			FileKind::TypeScript => self.typescript_module,
+		}
+	}
 }
 
 /// The kind of file we are parsing
@@ -89,14 +89,13 @@ pub struct Parser<'t> {
 impl<'t> Parser<'t> {
 	/// Make a new parser
 	pub fn new(tokens: TokenSource<'t>, file_id: usize, syntax: Syntax) -> Parser<'t> {
-		// TODO(RDambrosio016): Does TypeScript imply Module/Strict?
-		let strict = if syntax.file_kind == FileKind::Module {
+		let strict = if syntax.is_module() {
 			Some(StrictMode::Module)
 		} else {
 			None
 		};
 		let state = ParserState {
-			is_module: syntax.file_kind == FileKind::Module,
+			is_module: syntax.is_module(),
 			strict,
 			..ParserState::default()
 		};
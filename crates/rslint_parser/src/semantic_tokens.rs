@@ -0,0 +1,414 @@
+//! Classification of the tokens of a syntax tree, for the semantic highlighting of editors.
+//!
+//! Unlike the [`Highlighter`](rslint_lexer::highlight::Highlighter) of the lexer, which only
+//! looks at the tokens, the classification looks at the node that contains each identifier:
+//! the name of a function declaration is a [`SemanticTokenType::Function`] with the
+//! [`SemanticTokenModifiers::DECLARATION`] modifier, while the same identifier used in a call is
+//! only a [`SemanticTokenType::Function`].
+//!
+//! The types and the modifiers match the ones of the language server protocol, so the tokens can
+//! be encoded by a language server with [`SemanticTokenType::as_str`] as legend.
+//!
+//! ```
+//! use rslint_parser::parse_text;
+//! use rslint_parser::semantic_tokens::{semantic_tokens, SemanticTokenModifiers, SemanticTokenType};
+//!
+//! let parse = parse_text("function f(a) { return a; }", 0);
+//! let tokens = semantic_tokens(&parse.syntax(), None);
+//!
+//! assert_eq!(tokens[1].token_type, SemanticTokenType::Function);
+//! assert_eq!(tokens[1].modifiers, SemanticTokenModifiers::DECLARATION);
+//! assert_eq!(tokens[2].token_type, SemanticTokenType::Parameter);
+//! ```
+use crate::{SyntaxKind::*, SyntaxNode, SyntaxToken, TextRange, T};
+use std::ops::{BitOr, BitOrAssign};
+
+/// The kind of a token, its index in [`SemanticTokenType::ALL`] is its index in the legend
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum SemanticTokenType {
+	Keyword,
+	String,
+	Number,
+	Regexp,
+	Comment,
+	Function,
+	Method,
+	Parameter,
+	Variable,
+	Property,
+	Class,
+	Type,
+	EnumMember,
+}
+
+impl SemanticTokenType {
+	pub const ALL: [SemanticTokenType; 13] = [
+		SemanticTokenType::Keyword,
+		SemanticTokenType::String,
+		SemanticTokenType::Number,
+		SemanticTokenType::Regexp,
+		SemanticTokenType::Comment,
+		SemanticTokenType::Function,
+		SemanticTokenType::Method,
+		SemanticTokenType::Parameter,
+		SemanticTokenType::Variable,
+		SemanticTokenType::Property,
+		SemanticTokenType::Class,
+		SemanticTokenType::Type,
+		SemanticTokenType::EnumMember,
+	];
+
+	/// The name of the type in the language server protocol
+	pub const fn as_str(self) -> &'static str {
+		match self {
+			SemanticTokenType::Keyword => "keyword",
+			SemanticTokenType::String => "string",
+			SemanticTokenType::Number => "number",
+			SemanticTokenType::Regexp => "regexp",
+			SemanticTokenType::Comment => "comment",
+			SemanticTokenType::Function => "function",
+			SemanticTokenType::Method => "method",
+			SemanticTokenType::Parameter => "parameter",
+			SemanticTokenType::Variable => "variable",
+			SemanticTokenType::Property => "property",
+			SemanticTokenType::Class => "class",
+			SemanticTokenType::Type => "type",
+			SemanticTokenType::EnumMember => "enumMember",
+		}
+	}
+}
+
+/// A set of modifiers, the bit `n` is set when the modifier at the index `n` of
+/// [`SemanticTokenModifiers::ALL`] applies to the token
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub struct SemanticTokenModifiers(u32);
+
+impl SemanticTokenModifiers {
+	pub const NONE: Self = Self(0);
+	/// The token is the name of a declaration
+	pub const DECLARATION: Self = Self(1);
+	/// The value can't be assigned, like a `const` variable or a `readonly` property
+	pub const READONLY: Self = Self(1 << 1);
+	/// The function or the method is `async`
+	pub const ASYNC: Self = Self(1 << 2);
+
+	/// The names of the modifiers in the language server protocol
+	pub const ALL: [&'static str; 3] = ["declaration", "readonly", "async"];
+
+	pub const fn bits(self) -> u32 {
+		self.0
+	}
+
+	pub const fn contains(self, other: Self) -> bool {
+		self.0 & other.0 == other.0
+	}
+}
+
+impl BitOr for SemanticTokenModifiers {
+	type Output = Self;
+
+	fn bitor(self, other: Self) -> Self {
+		Self(self.0 | other.0)
+	}
+}
+
+impl BitOrAssign for SemanticTokenModifiers {
+	fn bitor_assign(&mut self, other: Self) {
+		self.0 |= other.0;
+	}
+}
+
+/// A classified token. The range can span multiple lines, like the range of a block comment
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct SemanticToken {
+	pub range: TextRange,
+	pub token_type: SemanticTokenType,
+	pub modifiers: SemanticTokenModifiers,
+}
+
+/// Classifies the tokens of the tree, in the order of the source. When `range` is set, only the
+/// tokens that intersect with it are returned.
+///
+/// The tokens that don't have a type, like the punctuation, are skipped
+pub fn semantic_tokens(root: &SyntaxNode, range: Option<TextRange>) -> Vec<SemanticToken> {
+	root.descendants_with_tokens()
+		.filter_map(|element| element.into_token())
+		.filter(|token| match range {
+			Some(range) => token.text_range().intersect(range).is_some(),
+			None => true,
+		})
+		.filter_map(|token| {
+			let (token_type, modifiers) = classify_token(&token)?;
+			Some(SemanticToken {
+				range: token.text_range(),
+				token_type,
+				modifiers,
+			})
+		})
+		.collect()
+}
+
+fn classify_token(token: &SyntaxToken) -> Option<(SemanticTokenType, SemanticTokenModifiers)> {
+	let token_type = match token.kind() {
+		COMMENT => SemanticTokenType::Comment,
+		STRING | TEMPLATE_CHUNK => SemanticTokenType::String,
+		BACKTICK if token.parent().map(|parent| parent.kind()) == Some(TEMPLATE) => {
+			SemanticTokenType::String
+		}
+		NUMBER | BIG_INT => SemanticTokenType::Number,
+		REGEX => SemanticTokenType::Regexp,
+		T![ident] => return Some(classify_ident(token)),
+		kind if kind.is_keyword() => SemanticTokenType::Keyword,
+		_ => return None,
+	};
+
+	Some((token_type, SemanticTokenModifiers::NONE))
+}
+
+fn classify_ident(token: &SyntaxToken) -> (SemanticTokenType, SemanticTokenModifiers) {
+	let parent = match token.parent() {
+		Some(parent) => parent,
+		None => return (SemanticTokenType::Variable, SemanticTokenModifiers::NONE),
+	};
+
+	match parent.kind() {
+		NAME => classify_name(&parent),
+		NAME_REF => (classify_reference(&parent), SemanticTokenModifiers::NONE),
+		TS_TYPE_NAME => (SemanticTokenType::Type, SemanticTokenModifiers::NONE),
+		TS_TYPE_PARAM => (SemanticTokenType::Type, SemanticTokenModifiers::DECLARATION),
+		TS_ENUM_MEMBER => (
+			SemanticTokenType::EnumMember,
+			SemanticTokenModifiers::DECLARATION | SemanticTokenModifiers::READONLY,
+		),
+		// the predefined types, like `string`
+		TS_ANY | TS_UNKNOWN | TS_NUMBER | TS_OBJECT | TS_BOOLEAN | TS_BIGINT | TS_STRING
+		| TS_SYMBOL | TS_UNDEFINED | TS_NEVER => (SemanticTokenType::Type, SemanticTokenModifiers::NONE),
+		// an identifier that is a direct child of a node is a contextual keyword, like `async`,
+		// `let` or `interface`
+		_ => (SemanticTokenType::Keyword, SemanticTokenModifiers::NONE),
+	}
+}
+
+/// Classifies the identifier of a `NAME` node, which is either a declaration or a member
+fn classify_name(name: &SyntaxNode) -> (SemanticTokenType, SemanticTokenModifiers) {
+	let declaration = SemanticTokenModifiers::DECLARATION;
+	let parent = match name.parent() {
+		Some(parent) => parent,
+		None => return (SemanticTokenType::Variable, declaration),
+	};
+
+	match parent.kind() {
+		FN_DECL | FN_EXPR => (
+			SemanticTokenType::Function,
+			declaration | async_modifier(&parent),
+		),
+		CLASS_DECL | CLASS_EXPR => (SemanticTokenType::Class, declaration),
+		METHOD | GETTER | SETTER | TS_METHOD_SIGNATURE => (
+			SemanticTokenType::Method,
+			declaration | async_modifier(&parent),
+		),
+		LITERAL_PROP | CLASS_PROP | TS_PROPERTY_SIGNATURE => (
+			SemanticTokenType::Property,
+			declaration | readonly_modifier(&parent),
+		),
+		PRIVATE_NAME => match parent.parent().map(|node| node.kind()) {
+			Some(PRIVATE_PROP) => (SemanticTokenType::Property, declaration),
+			_ => (SemanticTokenType::Property, SemanticTokenModifiers::NONE),
+		},
+		DOT_EXPR => {
+			let is_callee = parent.parent().map(|node| node.kind()) == Some(CALL_EXPR);
+			if is_callee {
+				(SemanticTokenType::Method, SemanticTokenModifiers::NONE)
+			} else {
+				(SemanticTokenType::Property, SemanticTokenModifiers::NONE)
+			}
+		}
+		TS_ENUM | TS_INTERFACE_DECL | TS_TYPE_ALIAS_DECL => (SemanticTokenType::Type, declaration),
+		_ => classify_binding(&parent),
+	}
+}
+
+/// Classifies a binding, by looking for the declaration that contains its pattern
+fn classify_binding(pattern: &SyntaxNode) -> (SemanticTokenType, SemanticTokenModifiers) {
+	let declaration = SemanticTokenModifiers::DECLARATION;
+	for ancestor in pattern.ancestors() {
+		match ancestor.kind() {
+			PARAMETER_LIST | CONSTRUCTOR_PARAMETERS | TS_CONSTRUCTOR_PARAM => {
+				return (SemanticTokenType::Parameter, declaration)
+			}
+			// the single parameter of an arrow function, like `a` in `a => a`
+			ARROW_EXPR => return (SemanticTokenType::Parameter, declaration),
+			VAR_DECL => {
+				let is_const = ancestor
+					.children_with_tokens()
+					.any(|child| child.kind() == T![const]);
+				let modifiers = if is_const {
+					declaration | SemanticTokenModifiers::READONLY
+				} else {
+					declaration
+				};
+				return (SemanticTokenType::Variable, modifiers);
+			}
+			SINGLE_PATTERN | OBJECT_PATTERN | ARRAY_PATTERN | ASSIGN_PATTERN | REST_PATTERN
+			| KEY_VALUE_PATTERN | LIST | DECLARATOR => continue,
+			_ => break,
+		}
+	}
+
+	(SemanticTokenType::Variable, declaration)
+}
+
+/// Classifies an identifier that references a declaration, from the way it's used
+fn classify_reference(name_ref: &SyntaxNode) -> SemanticTokenType {
+	let parent = match name_ref.parent() {
+		Some(parent) => parent,
+		None => return SemanticTokenType::Variable,
+	};
+	let is_first_child = parent.first_child().as_ref() == Some(name_ref);
+
+	match parent.kind() {
+		CALL_EXPR if is_first_child => SemanticTokenType::Function,
+		NEW_EXPR => SemanticTokenType::Class,
+		// the class that is extended
+		CLASS_DECL | CLASS_EXPR => SemanticTokenType::Class,
+		_ => SemanticTokenType::Variable,
+	}
+}
+
+fn async_modifier(node: &SyntaxNode) -> SemanticTokenModifiers {
+	// `async` is an identifier in function declarations, and a keyword in methods
+	let is_async = node.children_with_tokens().any(|child| {
+		child.kind() == T![async]
+			|| matches!(child.as_token(), Some(token) if token.text() == "async")
+	});
+
+	if is_async {
+		SemanticTokenModifiers::ASYNC
+	} else {
+		SemanticTokenModifiers::NONE
+	}
+}
+
+fn readonly_modifier(node: &SyntaxNode) -> SemanticTokenModifiers {
+	if node
+		.children_with_tokens()
+		.any(|child| child.kind() == T![readonly])
+	{
+		SemanticTokenModifiers::READONLY
+	} else {
+		SemanticTokenModifiers::NONE
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{semantic_tokens, SemanticTokenModifiers, SemanticTokenType};
+	use crate::{parse_module, parse_with_syntax, Syntax, TextRange, TextSize};
+
+	fn classify(source: &str, syntax: Syntax) -> Vec<(&str, SemanticTokenType, u32)> {
+		let parse = parse_with_syntax(source, 0, syntax);
+		semantic_tokens(&parse.syntax(), None)
+			.into_iter()
+			.map(|token| {
+				(
+					&source[token.range],
+					token.token_type,
+					token.modifiers.bits(),
+				)
+			})
+			.collect()
+	}
+
+	#[test]
+	fn classifies_javascript() {
+		use SemanticTokenType::*;
+		const DECLARATION: u32 = SemanticTokenModifiers::DECLARATION.bits();
+		const READONLY: u32 = SemanticTokenModifiers::READONLY.bits();
+		const ASYNC: u32 = SemanticTokenModifiers::ASYNC.bits();
+
+		let source = "// c
+async function f(a, { b }) { const x = a.b; obj.m(1, \"s\", /r/g, `t${x}`); }
+class C extends D { static p = 1; async m(q) {} }
+let o = new C();";
+
+		assert_eq!(
+			classify(source, Syntax::default().module()),
+			vec![
+				("// c", Comment, 0),
+				("async", Keyword, 0),
+				("function", Keyword, 0),
+				("f", Function, DECLARATION | ASYNC),
+				("a", Parameter, DECLARATION),
+				("b", Parameter, DECLARATION),
+				("const", Keyword, 0),
+				("x", Variable, DECLARATION | READONLY),
+				("a", Variable, 0),
+				("b", Property, 0),
+				("obj", Variable, 0),
+				("m", Method, 0),
+				("1", Number, 0),
+				("\"s\"", String, 0),
+				("/r/g", Regexp, 0),
+				("`", String, 0),
+				("t", String, 0),
+				("x", Variable, 0),
+				("`", String, 0),
+				("class", Keyword, 0),
+				("C", Class, DECLARATION),
+				("extends", Keyword, 0),
+				("D", Class, 0),
+				("static", Keyword, 0),
+				("p", Property, DECLARATION),
+				("1", Number, 0),
+				("async", Keyword, 0),
+				("m", Method, DECLARATION | ASYNC),
+				("q", Parameter, DECLARATION),
+				("let", Keyword, 0),
+				("o", Variable, DECLARATION),
+				("new", Keyword, 0),
+				("C", Class, 0),
+			]
+		);
+	}
+
+	#[test]
+	fn classifies_typescript() {
+		use SemanticTokenType::*;
+		const DECLARATION: u32 = SemanticTokenModifiers::DECLARATION.bits();
+		const READONLY: u32 = SemanticTokenModifiers::READONLY.bits();
+
+		let source = "enum E { A = 1 }
+interface I { readonly r: string }
+type T<U> = U;";
+
+		assert_eq!(
+			classify(source, Syntax::default().typescript()),
+			vec![
+				("enum", Keyword, 0),
+				("E", Type, DECLARATION),
+				("A", EnumMember, DECLARATION | READONLY),
+				("1", Number, 0),
+				("interface", Keyword, 0),
+				("I", Type, DECLARATION),
+				("readonly", Keyword, 0),
+				("r", Property, DECLARATION | READONLY),
+				("string", Type, 0),
+				("type", Keyword, 0),
+				("T", Type, DECLARATION),
+				("U", Type, DECLARATION),
+				("U", Type, 0),
+			]
+		);
+	}
+
+	#[test]
+	fn filters_range() {
+		let parse = parse_module("let a = 1;\nlet b = 2;", 0);
+		let range = TextRange::new(TextSize::from(11), TextSize::from(16));
+		let tokens = semantic_tokens(&parse.syntax(), Some(range));
+
+		assert_eq!(tokens.len(), 2);
+		assert_eq!(tokens[0].range, TextRange::new(11.into(), 14.into()));
+		assert_eq!(tokens[1].token_type, SemanticTokenType::Variable);
+	}
+}